@@ -0,0 +1,2 @@
+-- AlterTable
+ALTER TABLE "workplaces" ADD COLUMN "timezone" TEXT NOT NULL DEFAULT 'Asia/Tokyo';
@@ -0,0 +1,21 @@
+-- CreateTable
+CREATE TABLE "attendance_record_audits" (
+    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "attendance_record_id" INTEGER NOT NULL,
+    "workplace_id" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL,
+    "action" TEXT NOT NULL,
+    "channel" TEXT NOT NULL,
+    "api_key_id" INTEGER,
+    "previous_event" TEXT,
+    "previous_recorded_at" DATETIME,
+    "event" TEXT,
+    "recorded_at" DATETIME,
+    "created_at" DATETIME NOT NULL
+);
+
+-- CreateIndex
+CREATE INDEX "index_attendance_record_audits_on_attendance_record_id" ON "attendance_record_audits"("attendance_record_id");
+
+-- CreateIndex
+CREATE INDEX "index_attendance_record_audits_on_workplace_id" ON "attendance_record_audits"("workplace_id");
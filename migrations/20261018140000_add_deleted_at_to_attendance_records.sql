@@ -0,0 +1,5 @@
+-- AlterTable
+ALTER TABLE "attendance_records" ADD COLUMN "deleted_at" DATETIME;
+
+-- CreateIndex
+CREATE INDEX "index_attendance_records_on_deleted_at" ON "attendance_records"("deleted_at");
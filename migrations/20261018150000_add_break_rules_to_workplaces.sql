@@ -0,0 +1,2 @@
+-- AlterTable
+ALTER TABLE "workplaces" ADD COLUMN "break_rules" TEXT NOT NULL DEFAULT '360:45,480:60';
@@ -0,0 +1,4 @@
+-- AlterTable
+ALTER TABLE "workplaces" ADD COLUMN "daily_overtime_threshold_minutes" INTEGER NOT NULL DEFAULT 480;
+ALTER TABLE "workplaces" ADD COLUMN "weekly_overtime_threshold_minutes" INTEGER NOT NULL DEFAULT 2400;
+ALTER TABLE "workplaces" ADD COLUMN "rest_days" TEXT NOT NULL DEFAULT 'sun';
@@ -0,0 +1,14 @@
+-- CreateTable
+CREATE TABLE "calendar_feeds" (
+    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "workplace_id" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL,
+    "digest" TEXT NOT NULL,
+    "created_at" DATETIME NOT NULL
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "index_calendar_feeds_on_workplace_id" ON "calendar_feeds"("workplace_id");
+
+-- CreateIndex
+CREATE UNIQUE INDEX "index_calendar_feeds_on_digest" ON "calendar_feeds"("digest");
@@ -0,0 +1,2 @@
+-- AlterTable
+ALTER TABLE "workplaces" ADD COLUMN "closing_day" INTEGER NOT NULL DEFAULT 31;
@@ -0,0 +1,2 @@
+-- AlterTable
+ALTER TABLE "workplaces" ADD COLUMN "archived_at" DATETIME;
@@ -0,0 +1,50 @@
+-- CreateTable
+CREATE TABLE "workplace_memberships" (
+    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "workplace_id" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL,
+    "role" TEXT NOT NULL,
+    "created_at" DATETIME NOT NULL,
+    "updated_at" DATETIME NOT NULL
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "index_workplace_memberships_on_workplace_id_and_user_id" ON "workplace_memberships"("workplace_id", "user_id");
+
+-- CreateIndex
+CREATE INDEX "index_workplace_memberships_on_user_id" ON "workplace_memberships"("user_id");
+
+-- DeleteOrphans
+-- Records of workplaces that no longer exist have no owner to be attributed to.
+DELETE FROM "attendance_record_audits" WHERE "attendance_record_id" IN (SELECT "id" FROM "attendance_records" WHERE "workplace_id" NOT IN (SELECT "id" FROM "workplaces"));
+DELETE FROM "attendance_records" WHERE "workplace_id" NOT IN (SELECT "id" FROM "workplaces");
+
+-- RedefineTables
+-- Existing records are attributed to the owner of their workplace.
+CREATE TABLE "new_attendance_records" (
+    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "workplace_id" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL,
+    "event" TEXT NOT NULL,
+    "recorded_at" DATETIME NOT NULL,
+    "created_at" DATETIME NOT NULL,
+    "deleted_at" DATETIME
+);
+INSERT INTO "new_attendance_records" ("id", "workplace_id", "user_id", "event", "recorded_at", "created_at", "deleted_at") SELECT "attendance_records"."id", "attendance_records"."workplace_id", "workplaces"."user_id", "attendance_records"."event", "attendance_records"."recorded_at", "attendance_records"."created_at", "attendance_records"."deleted_at" FROM "attendance_records" INNER JOIN "workplaces" ON "workplaces"."id" = "attendance_records"."workplace_id";
+DROP TABLE "attendance_records";
+ALTER TABLE "new_attendance_records" RENAME TO "attendance_records";
+
+-- CreateIndex
+CREATE INDEX "index_attendance_records_on_workplace_id" ON "attendance_records"("workplace_id");
+
+-- CreateIndex
+CREATE INDEX "index_attendance_records_on_user_id" ON "attendance_records"("user_id");
+
+-- CreateIndex
+CREATE INDEX "index_attendance_records_on_deleted_at" ON "attendance_records"("deleted_at");
+
+-- DropIndex
+DROP INDEX "index_calendar_feeds_on_workplace_id";
+
+-- CreateIndex
+CREATE UNIQUE INDEX "index_calendar_feeds_on_workplace_id_and_user_id" ON "calendar_feeds"("workplace_id", "user_id");
@@ -0,0 +1,20 @@
+-- CreateTable
+CREATE TABLE "timesheets" (
+    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "workplace_id" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL,
+    "year" INTEGER NOT NULL,
+    "month" INTEGER NOT NULL,
+    "state" TEXT NOT NULL,
+    "comment" TEXT,
+    "starts_at" DATETIME NOT NULL,
+    "ends_at" DATETIME NOT NULL,
+    "submitted_at" DATETIME,
+    "reviewed_by" INTEGER,
+    "reviewed_at" DATETIME,
+    "created_at" DATETIME NOT NULL,
+    "updated_at" DATETIME NOT NULL
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "index_timesheets_on_workplace_id_and_user_id_and_year_and_month" ON "timesheets"("workplace_id", "user_id", "year", "month");
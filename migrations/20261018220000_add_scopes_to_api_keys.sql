@@ -0,0 +1,5 @@
+-- AlterTable
+ALTER TABLE "api_keys" ADD COLUMN "workplace_ids" TEXT NOT NULL DEFAULT '';
+
+-- AlterTable
+ALTER TABLE "api_keys" ADD COLUMN "actions" TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,8 @@
+-- AlterTable
+ALTER TABLE "api_keys" ADD COLUMN "last_used_at" DATETIME;
+
+-- AlterTable
+ALTER TABLE "api_keys" ADD COLUMN "last_used_ip" TEXT;
+
+-- AlterTable
+ALTER TABLE "api_keys" ADD COLUMN "expires_at" DATETIME;
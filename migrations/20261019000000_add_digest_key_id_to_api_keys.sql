@@ -0,0 +1,2 @@
+-- AlterTable
+ALTER TABLE "api_keys" ADD COLUMN "digest_key_id" TEXT;
@@ -0,0 +1,5 @@
+-- AlterTable
+ALTER TABLE "api_keys" ADD COLUMN "public_id" TEXT;
+
+-- CreateIndex
+CREATE UNIQUE INDEX "index_api_keys_on_public_id" ON "api_keys"("public_id");
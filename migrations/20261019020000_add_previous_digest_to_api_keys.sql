@@ -0,0 +1,8 @@
+-- AlterTable
+ALTER TABLE "api_keys" ADD COLUMN "previous_digest" TEXT;
+
+-- AlterTable
+ALTER TABLE "api_keys" ADD COLUMN "previous_digest_expires_at" DATETIME;
+
+-- CreateIndex
+CREATE INDEX "index_api_keys_on_previous_digest" ON "api_keys"("previous_digest");
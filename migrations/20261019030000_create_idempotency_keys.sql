@@ -0,0 +1,18 @@
+-- CreateTable
+CREATE TABLE "idempotency_keys" (
+    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "api_key_id" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL,
+    "key" TEXT NOT NULL,
+    "request_uri" TEXT NOT NULL,
+    "response_status" INTEGER,
+    "response_body" TEXT,
+    "created_at" DATETIME NOT NULL,
+    "completed_at" DATETIME
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "index_idempotency_keys_on_api_key_id_and_key" ON "idempotency_keys"("api_key_id", "key");
+
+-- CreateIndex
+CREATE INDEX "index_idempotency_keys_on_created_at" ON "idempotency_keys"("created_at");
@@ -0,0 +1,33 @@
+use actix_web::{dev::ServiceRequest, middleware::Logger};
+
+const FORMAT: &str = "%a %t \"%{request_line}xi\" %s %b \"%{Referer}i\" \"%{User-Agent}i\" %T";
+const CALENDAR_FEED_PREFIX: &str = "/calendar_feeds/";
+const MASKED_TOKEN: &str = "[FILTERED]";
+
+/// The access log of the server. Calendar clients authenticate with the feed token in the path,
+/// so it is masked in the logged request line instead of `%r` writing it out.
+pub fn logger() -> Logger {
+    Logger::new(FORMAT).custom_request_replace("request_line", request_line)
+}
+
+/// The first line of the request, as `%r` logs it but with any calendar feed token masked.
+pub fn request_line(request: &ServiceRequest) -> String {
+    let path = request.path();
+    let path = match path.strip_prefix(CALENDAR_FEED_PREFIX) {
+        Some(feed) => {
+            let extension = feed.rfind('.').map(|index| &feed[index..]).unwrap_or("");
+            format!("{CALENDAR_FEED_PREFIX}{MASKED_TOKEN}{extension}")
+        }
+        None => path.to_string(),
+    };
+    match request.query_string() {
+        "" => format!("{} {} {:?}", request.method(), path, request.version()),
+        query => format!(
+            "{} {}?{} {:?}",
+            request.method(),
+            path,
+            query,
+            request.version()
+        ),
+    }
+}
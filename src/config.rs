@@ -1,4 +1,5 @@
 use anyhow::Result;
+use chrono::TimeDelta;
 use serde::Deserialize;
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
@@ -27,6 +28,79 @@ pub struct ServerConfig {
     pub port: u16,
 }
 
+#[derive(Clone, Deserialize)]
+pub struct TrashConfig {
+    pub retention_days: u32,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ApiKeysConfig {
+    /// How long the token replaced by a rotation keeps working.
+    pub rotation_grace_period_minutes: u32,
+}
+
+impl Default for ApiKeysConfig {
+    fn default() -> Self {
+        Self {
+            rotation_grace_period_minutes: 24 * 60,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct IdempotencyConfig {
+    /// How long a response is replayed for a retried `Idempotency-Key`.
+    pub window_minutes: u32,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            window_minutes: 24 * 60,
+        }
+    }
+}
+
+impl IdempotencyConfig {
+    pub fn window(&self) -> TimeDelta {
+        TimeDelta::minutes(self.window_minutes.into())
+    }
+}
+
+/// Allows `requests` per window of `window_seconds`.
+#[derive(Clone, Deserialize)]
+pub struct RateLimitBucketConfig {
+    pub requests: u32,
+    pub window_seconds: u64,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct RateLimitConfig {
+    pub per_key: RateLimitBucketConfig,
+    pub per_ip: RateLimitBucketConfig,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            per_key: RateLimitBucketConfig {
+                requests: 60,
+                window_seconds: 60,
+            },
+            per_ip: RateLimitBucketConfig {
+                requests: 300,
+                window_seconds: 60,
+            },
+        }
+    }
+}
+
 #[derive(Clone, Deserialize)]
 #[allow(dead_code)]
 pub struct ApplicationConfig {
@@ -34,6 +108,14 @@ pub struct ApplicationConfig {
     pub database: DatabaseConfig,
     pub frontend: FrontendConfig,
     pub server: ServerConfig,
+    #[serde(default)]
+    pub trash: TrashConfig,
+    #[serde(default)]
+    pub api_keys: ApiKeysConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub idempotency: IdempotencyConfig,
 }
 
 impl ApplicationConfig {
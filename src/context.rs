@@ -1,7 +1,10 @@
 use anyhow::Result;
 use sqlx::{Pool, Sqlite, sqlite::SqlitePoolOptions};
 
-use crate::{config::ApplicationConfig, repositories::RdbRepositories, secrets::Secrets};
+use crate::{
+    config::ApplicationConfig, rate_limiter::RateLimiter, repositories::RdbRepositories,
+    secrets::Secrets,
+};
 
 #[derive(Clone)]
 pub struct DatabaseContext {
@@ -22,6 +25,7 @@ pub struct AppState {
     pub database: DatabaseContext,
     pub repositories: RdbRepositories,
     pub secrets: Secrets,
+    pub rate_limiter: RateLimiter,
 }
 
 impl AppState {
@@ -34,6 +38,7 @@ impl AppState {
             repositories,
             secrets,
             database,
+            rate_limiter: RateLimiter::default(),
         };
         Ok(app_state)
     }
@@ -25,29 +25,47 @@ impl From<SqlxError> for DatabaseError {
 #[derive(Debug, Error)]
 #[allow(dead_code)]
 pub enum PerRequestError {
+    #[error("bad request")]
+    BadRequest,
+
     #[error("not found")]
     NotFound,
 
     #[error("unauthorized")]
     Unauthorized,
 
+    #[error("forbidden")]
+    Forbidden,
+
+    #[error("conflict")]
+    Conflict(&'static str),
+
     #[error("server error")]
     ServerError,
 }
 
 impl ResponseError for PerRequestError {
     fn error_response(&self) -> HttpResponse {
-        let response_json = json!({
-            "error": self.to_string(),
-        });
+        let response_json = match self {
+            Self::Conflict(reason) => json!({
+                "error": self.to_string(),
+                "reason": reason,
+            }),
+            _ => json!({
+                "error": self.to_string(),
+            }),
+        };
 
         HttpResponse::build(self.status_code()).json(response_json)
     }
 
     fn status_code(&self) -> StatusCode {
         match *self {
+            Self::BadRequest => StatusCode::BAD_REQUEST,
             Self::NotFound => StatusCode::NOT_FOUND,
             Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::Conflict(_) => StatusCode::CONFLICT,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -74,6 +92,12 @@ impl From<SessionInsertError> for PerRequestError {
     }
 }
 
+impl From<csv::Error> for PerRequestError {
+    fn from(_value: csv::Error) -> Self {
+        Self::ServerError
+    }
+}
+
 impl From<anyhow::Error> for PerRequestError {
     fn from(_value: anyhow::Error) -> Self {
         Self::ServerError
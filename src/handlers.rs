@@ -3,15 +3,22 @@ use actix_web::{
     web::{ServiceConfig, get, resource, scope},
 };
 
-use crate::middlewares::{require_api_key::RequireApiKey, require_signin::RequireSignin};
+use crate::middlewares::{
+    rate_limit::RateLimit, require_api_key::RequireApiKey, require_signin::RequireSignin,
+};
 
 mod api;
 mod api_keys;
 mod attendance_records;
+mod attendance_registration;
 mod auth;
+mod calendar_feeds;
 mod current_user;
+mod period_lock;
 mod signout;
+mod timesheets;
 mod views;
+mod workplace_memberships;
 mod workplaces;
 
 pub fn routes(config: &mut ServiceConfig) {
@@ -19,9 +26,12 @@ pub fn routes(config: &mut ServiceConfig) {
         .service(
             scope("/api")
                 .wrap(RequireApiKey::new())
+                // registered last so that it runs first, ahead of looking up the key
+                .wrap(RateLimit::new())
                 .configure(api::routes),
         )
         .service(scope("/auth/google").configure(auth::routes))
+        .service(scope("/calendar_feeds").configure(calendar_feeds::feed_routes))
         .service(scope("/signout").configure(signout::routes))
         .service(resource("/ping").route(get().to(HttpResponse::NoContent)))
         .service(
@@ -39,5 +49,13 @@ fn backend_routes(config: &mut ServiceConfig) {
             scope("/workplaces/{workplace_id}/attendance_records")
                 .configure(attendance_records::routes),
         )
+        .service(
+            scope("/workplaces/{workplace_id}/calendar_feed").configure(calendar_feeds::routes),
+        )
+        .service(scope("/workplaces/{workplace_id}/timesheets").configure(timesheets::routes))
+        .service(
+            scope("/workplaces/{workplace_id}/memberships")
+                .configure(workplace_memberships::routes),
+        )
         .service(scope("/workplaces").configure(workplaces::routes));
 }
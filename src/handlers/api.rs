@@ -1,17 +1,23 @@
 use std::sync::Arc;
 
-use actix_web::web::{Data, Path, ReqData, ServiceConfig, post};
-use actix_web::{HttpResponse, Result};
+use actix_web::http::{StatusCode, header::ContentType};
+use actix_web::web::{Data, Path, Query, ReqData, ServiceConfig, get, post};
+use actix_web::{HttpRequest, HttpResponse, Result};
+use chrono::Utc;
+use serde::Deserialize;
 use serde_json::json;
 
+use super::attendance_registration::{AttendanceRegistration, ConflictPolicy, RegistrationOutcome};
+use super::{attendance_records, timesheets};
 use crate::{
     AppState,
     errors::PerRequestError,
-    models::{User, WorkplaceId, attendance_record::Event},
+    models::{Actor, User, WorkplaceId, attendance_record::Event},
+    repositories::RepositoryFactory,
 };
 
-mod attendance_registration;
-use attendance_registration::AttendanceRegistration;
+mod idempotency;
+use idempotency::Idempotency;
 
 pub(super) fn routes(config: &mut ServiceConfig) {
     config
@@ -19,46 +25,181 @@ pub(super) fn routes(config: &mut ServiceConfig) {
         .route(
             "/workplaces/{workplace_id}/clock_outs",
             post().to(clock_out),
+        )
+        .route(
+            "/workplaces/{workplace_id}/break_starts",
+            post().to(break_start),
+        )
+        .route(
+            "/workplaces/{workplace_id}/break_ends",
+            post().to(break_end),
+        )
+        .route(
+            "/workplaces/{workplace_id}/attendance_records",
+            get().to(attendance_records::index),
+        )
+        .route(
+            "/workplaces/{workplace_id}/timesheets/{year}/{month}",
+            get().to(timesheets::show),
         );
 }
 
+#[derive(Deserialize)]
+struct ClockParameters {
+    #[serde(default)]
+    on_conflict: ConflictPolicy,
+}
+
 async fn clock_in(
     app_state: Data<AppState>,
     current_user: ReqData<User>,
+    actor: ReqData<Actor>,
     path: Path<WorkplaceId>,
+    params: Query<ClockParameters>,
+    request: HttpRequest,
 ) -> Result<HttpResponse, PerRequestError> {
-    create_clock(app_state, current_user, path, Event::ClockIn).await
+    create_clock(
+        app_state,
+        current_user,
+        actor,
+        path,
+        params,
+        request,
+        Event::ClockIn,
+    )
+    .await
 }
 
 async fn clock_out(
     app_state: Data<AppState>,
     current_user: ReqData<User>,
+    actor: ReqData<Actor>,
+    path: Path<WorkplaceId>,
+    params: Query<ClockParameters>,
+    request: HttpRequest,
+) -> Result<HttpResponse, PerRequestError> {
+    create_clock(
+        app_state,
+        current_user,
+        actor,
+        path,
+        params,
+        request,
+        Event::ClockOut,
+    )
+    .await
+}
+
+async fn break_start(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    actor: ReqData<Actor>,
+    path: Path<WorkplaceId>,
+    params: Query<ClockParameters>,
+    request: HttpRequest,
+) -> Result<HttpResponse, PerRequestError> {
+    create_clock(
+        app_state,
+        current_user,
+        actor,
+        path,
+        params,
+        request,
+        Event::BreakStart,
+    )
+    .await
+}
+
+async fn break_end(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    actor: ReqData<Actor>,
     path: Path<WorkplaceId>,
+    params: Query<ClockParameters>,
+    request: HttpRequest,
 ) -> Result<HttpResponse, PerRequestError> {
-    create_clock(app_state, current_user, path, Event::ClockOut).await
+    create_clock(
+        app_state,
+        current_user,
+        actor,
+        path,
+        params,
+        request,
+        Event::BreakEnd,
+    )
+    .await
 }
 
 async fn create_clock(
     app_state: Data<AppState>,
     current_user: ReqData<User>,
+    actor: ReqData<Actor>,
     path: Path<WorkplaceId>,
+    params: Query<ClockParameters>,
+    request: HttpRequest,
     event: Event,
 ) -> Result<HttpResponse, PerRequestError> {
-    let workplace_id = path.into_inner();
+    let idempotency = Idempotency::new(&app_state, &actor, &request)?;
+    if let Some(response) = idempotency.begin().await? {
+        return Ok(response);
+    }
+
+    let result = register_clock(
+        &app_state,
+        &current_user,
+        &actor,
+        path.into_inner(),
+        params.on_conflict,
+        event,
+    )
+    .await;
+    let (status, body) = match result {
+        Ok(response) => response,
+        Err(error) => {
+            idempotency.release().await;
+            return Err(error);
+        }
+    };
+    idempotency.complete(status, &body).await;
 
-    let registration = AttendanceRegistration::new(Arc::clone(&app_state.into_inner()));
-    let attendance_record = registration
-        .execute(&current_user, workplace_id, event)
+    let response = HttpResponse::build(status)
+        .content_type(ContentType::json())
+        .body(body);
+    Ok(response)
+}
+
+async fn register_clock(
+    app_state: &Data<AppState>,
+    current_user: &User,
+    actor: &Actor,
+    workplace_id: WorkplaceId,
+    policy: ConflictPolicy,
+    event: Event,
+) -> Result<(StatusCode, String), PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(current_user, workplace_id)
         .await?;
 
+    let registration = AttendanceRegistration::new(Arc::clone(app_state));
+    let outcome = registration
+        .execute(actor, &workplace, event, &Utc::now().into(), policy)
+        .await?;
+    let (status, attendance_record) = match outcome {
+        RegistrationOutcome::Created(record) => (StatusCode::CREATED, record),
+        RegistrationOutcome::Merged(record) => (StatusCode::OK, record),
+    };
+
     let response_json = json!({
         "attendanceRecord": {
             "id": attendance_record.id,
             "workplaceId": attendance_record.workplace_id,
+            "userId": attendance_record.user_id,
             "event": attendance_record.event,
             "recordedAt": attendance_record.recorded_at,
+            "localRecordedAt": attendance_record.recorded_at.with_timezone(&*workplace.timezone),
         },
     });
-    let response = HttpResponse::Created().json(response_json);
-    Ok(response)
+    Ok((status, response_json.to_string()))
 }
@@ -0,0 +1,147 @@
+use actix_web::{
+    HttpRequest, HttpResponse,
+    http::{StatusCode, header::ContentType},
+};
+use chrono::{TimeDelta, Utc};
+
+use crate::{
+    AppState,
+    errors::PerRequestError,
+    models::{Actor, IdempotencyKey, Timestamp},
+    repositories::RepositoryFactory,
+};
+
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Honors the `Idempotency-Key` header of a request made with an API key: a retry
+/// with the same key gets the stored response instead of running the request again.
+/// The key is reserved before the request runs, so a retry arriving meanwhile is
+/// turned away instead of running it twice.
+pub(super) struct Idempotency<'a> {
+    app_state: &'a AppState,
+    idempotency_key: Option<IdempotencyKey>,
+}
+
+impl<'a> Idempotency<'a> {
+    pub(super) fn new(
+        app_state: &'a AppState,
+        actor: &Actor,
+        request: &HttpRequest,
+    ) -> Result<Self, PerRequestError> {
+        let key = match request.headers().get(IDEMPOTENCY_KEY_HEADER) {
+            Some(value) => {
+                let key = value.to_str().map_err(|_| PerRequestError::BadRequest)?;
+                if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+                    return Err(PerRequestError::BadRequest);
+                }
+                Some(key.to_string())
+            }
+            None => None,
+        };
+        let idempotency_key = key
+            .zip(actor.api_key_id)
+            .map(|(key, api_key_id)| IdempotencyKey {
+                api_key_id,
+                user_id: actor.user_id,
+                key,
+                request_uri: request.uri().to_string(),
+                response_status: None,
+                response_body: None,
+                created_at: Timestamp::from(Utc::now()),
+                completed_at: None,
+            });
+        Ok(Self {
+            app_state,
+            idempotency_key,
+        })
+    }
+
+    /// Reserves the key for this request, or returns the stored response for a
+    /// retried key. Reusing a key for another request, or retrying while the
+    /// first request is still running, is a conflict.
+    pub(super) async fn begin(&self) -> Result<Option<HttpResponse>, PerRequestError> {
+        let Some(idempotency_key) = &self.idempotency_key else {
+            return Ok(None);
+        };
+        let repository = self.app_state.repositories.idempotency_key();
+        if let Some(stored) = repository
+            .find(idempotency_key.api_key_id, &idempotency_key.key)
+            .await?
+        {
+            if !stored.is_expired_at(&idempotency_key.created_at, self.window()) {
+                return Self::replay(idempotency_key, stored).map(Some);
+            }
+            let expired_before = Timestamp::from(*idempotency_key.created_at - self.window());
+            repository.purge_expired(&expired_before).await?;
+        }
+
+        if !repository.reserve(idempotency_key).await? {
+            return Err(PerRequestError::Conflict("idempotency-key-in-progress"));
+        }
+        Ok(None)
+    }
+
+    /// Stores the response so that retries within the window replay it. As the request has
+    /// already taken effect, a failure to store it releases the key instead of leaving it
+    /// pending, and retries run the request again.
+    pub(super) async fn complete(&self, status: StatusCode, body: &str) {
+        let Some(idempotency_key) = &self.idempotency_key else {
+            return;
+        };
+        let completed = IdempotencyKey {
+            response_status: Some(status.as_u16()),
+            response_body: Some(body.to_string()),
+            completed_at: Some(Timestamp::from(Utc::now())),
+            ..idempotency_key.clone()
+        };
+        let repository = self.app_state.repositories.idempotency_key();
+        if repository.complete(&completed).await.is_err() {
+            log::warn!(
+                "Releasing idempotency key {:?} whose response could not be stored",
+                idempotency_key.key
+            );
+            self.release().await;
+        }
+    }
+
+    /// Frees the key of a failed request, so that it can be retried. A key that cannot be
+    /// freed stays pending until it expires, which must not hide how the request failed.
+    pub(super) async fn release(&self) {
+        let Some(idempotency_key) = &self.idempotency_key else {
+            return;
+        };
+        let repository = self.app_state.repositories.idempotency_key();
+        if repository.release(idempotency_key).await.is_err() {
+            log::warn!(
+                "Idempotency key {:?} is left pending until it expires",
+                idempotency_key.key
+            );
+        }
+    }
+
+    fn replay(
+        idempotency_key: &IdempotencyKey,
+        stored: IdempotencyKey,
+    ) -> Result<HttpResponse, PerRequestError> {
+        if stored.request_uri != idempotency_key.request_uri {
+            return Err(PerRequestError::Conflict("idempotency-key-reused"));
+        }
+        if stored.is_pending() {
+            return Err(PerRequestError::Conflict("idempotency-key-in-progress"));
+        }
+
+        let status = stored
+            .response_status
+            .and_then(|status| StatusCode::from_u16(status).ok())
+            .ok_or(PerRequestError::ServerError)?;
+        let response = HttpResponse::build(status)
+            .content_type(ContentType::json())
+            .body(stored.response_body.unwrap_or_default());
+        Ok(response)
+    }
+
+    fn window(&self) -> TimeDelta {
+        self.app_state.config.idempotency.window()
+    }
+}
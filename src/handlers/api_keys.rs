@@ -9,18 +9,22 @@ use super::views::ApiKeyView;
 use crate::{
     AppState,
     errors::PerRequestError,
-    models::{ApiKeyId, User},
+    models::{ApiKeyId, ApiKeyScope, ScopedActions, ScopedWorkplaces, Timestamp, User},
     repositories::RepositoryFactory,
 };
 
 mod registration;
 use registration::ApiKeyRegistration;
 
+mod rotation;
+use rotation::ApiKeyRotation;
+
 pub(super) fn routes(config: &mut ServiceConfig) {
     config
         .route("", get().to(index))
         .route("", post().to(create))
-        .route("/{id}", delete().to(destroy));
+        .route("/{id}", delete().to(destroy))
+        .route("/{id}/rotate", post().to(rotate));
 }
 
 async fn index(
@@ -45,6 +49,11 @@ async fn index(
 #[derive(Deserialize)]
 struct CreatingApiKeyForm {
     name: String,
+    #[serde(default)]
+    workplace_ids: ScopedWorkplaces,
+    #[serde(default)]
+    actions: ScopedActions,
+    expires_at: Option<Timestamp>,
 }
 
 async fn create(
@@ -52,7 +61,18 @@ async fn create(
     current_user: ReqData<User>,
     form: Form<CreatingApiKeyForm>,
 ) -> Result<HttpResponse, PerRequestError> {
-    let registration = ApiKeyRegistration::new(&app_state, &current_user, &form.name);
+    let form = form.into_inner();
+    let scope = ApiKeyScope {
+        workplace_ids: form.workplace_ids,
+        actions: form.actions,
+    };
+    let registration = ApiKeyRegistration::new(
+        &app_state,
+        &current_user,
+        &form.name,
+        &scope,
+        form.expires_at.as_ref(),
+    );
     let registration_details = registration.execute().await?;
 
     let response_json = json!({
@@ -68,6 +88,22 @@ struct ApiKeyPath {
     id: ApiKeyId,
 }
 
+async fn rotate(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<ApiKeyPath>,
+) -> Result<HttpResponse, PerRequestError> {
+    let rotation = ApiKeyRotation::new(&app_state, &current_user, &path.id);
+    let registration_details = rotation.execute().await?;
+
+    let response_json = json!({
+        "api_key": registration_details,
+        "apiKey": registration_details,
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
 async fn destroy(
     app_state: Data<AppState>,
     current_user: ReqData<User>,
@@ -1,57 +1,134 @@
 use base64::{Engine, engine::general_purpose::URL_SAFE};
+use chrono::Utc;
 use serde::Serialize;
+use thiserror::Error;
 
 use crate::{
     AppState,
-    errors::DatabaseError,
-    models::{ApiKeyId, TokenDigester, TokenGenerator, User},
+    errors::{DatabaseError, PerRequestError},
+    models::{
+        ApiKey, ApiKeyId, ApiKeyScope, TOKEN_PREFIX, Timestamp, TokenDigest, TokenGenerator, User,
+    },
     repositories::RepositoryFactory,
 };
 
+#[derive(Debug, Error)]
+pub(super) enum RegistrationError {
+    #[error("database error")]
+    Database(#[from] DatabaseError),
+
+    #[error("scope names a workplace the user cannot access")]
+    UnknownWorkplace,
+
+    #[error("expiry is not in the future")]
+    AlreadyExpired,
+}
+
+impl From<RegistrationError> for PerRequestError {
+    fn from(value: RegistrationError) -> Self {
+        match value {
+            RegistrationError::Database(error) => error.into(),
+            RegistrationError::UnknownWorkplace | RegistrationError::AlreadyExpired => {
+                Self::BadRequest
+            }
+        }
+    }
+}
+
 pub(super) struct ApiKeyRegistration<'a> {
     app_state: &'a AppState,
     user: &'a User,
     name: &'a str,
+    scope: &'a ApiKeyScope,
+    expires_at: Option<&'a Timestamp>,
 }
 
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 pub(super) struct RegistationDetails {
     id: ApiKeyId,
     name: String,
+    public_id: Option<String>,
     token: String,
 }
 
+impl RegistationDetails {
+    pub(super) fn new(api_key: ApiKey, token: String) -> Self {
+        Self {
+            id: api_key.id,
+            name: api_key.name,
+            public_id: api_key.public_id,
+            token,
+        }
+    }
+}
+
 impl<'a> ApiKeyRegistration<'a> {
-    pub(super) fn new(app_state: &'a AppState, user: &'a User, name: &'a str) -> Self {
+    pub(super) fn new(
+        app_state: &'a AppState,
+        user: &'a User,
+        name: &'a str,
+        scope: &'a ApiKeyScope,
+        expires_at: Option<&'a Timestamp>,
+    ) -> Self {
         Self {
             app_state,
             user,
             name,
+            scope,
+            expires_at,
         }
     }
 
-    pub(super) async fn execute(self) -> Result<RegistationDetails, DatabaseError> {
-        let token = self.generate_token();
-        let digest = self.digest_token(&token);
+    pub(super) async fn execute(self) -> Result<RegistationDetails, RegistrationError> {
+        if self
+            .expires_at
+            .is_some_and(|expires_at| **expires_at <= Utc::now())
+        {
+            return Err(RegistrationError::AlreadyExpired);
+        }
+        self.ensure_workplaces_accessible().await?;
+
+        let public_id = TokenGenerator.generate_public_id();
+        let token = generate_token(&public_id);
+        let digest = digest_token(self.app_state, &token);
 
         let repository = self.app_state.repositories.api_key();
-        let api_key = repository.create(self.user, self.name, &digest).await?;
+        let api_key = repository
+            .create(
+                self.user,
+                self.name,
+                &public_id,
+                &digest,
+                self.scope,
+                self.expires_at,
+            )
+            .await?;
 
-        let details = RegistationDetails {
-            id: api_key.id,
-            name: api_key.name,
-            token,
-        };
-        Ok(details)
+        Ok(RegistationDetails::new(api_key, token))
     }
 
-    fn generate_token(&self) -> String {
-        let raw_token = TokenGenerator.generate();
-        URL_SAFE.encode(&raw_token)
+    async fn ensure_workplaces_accessible(&self) -> Result<(), RegistrationError> {
+        let repository = self.app_state.repositories.workplace();
+        for workplace_id in self.scope.workplace_ids.ids() {
+            match repository.find(self.user, *workplace_id).await {
+                Ok(_) => {}
+                Err(DatabaseError::RecordNotFound) => {
+                    return Err(RegistrationError::UnknownWorkplace);
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Ok(())
     }
+}
 
-    fn digest_token(&self, token: &str) -> String {
-        let digester = TokenDigester::new(&self.app_state.secrets.api_key.digesting_secret_key);
-        digester.digest_token(token).unwrap()
-    }
+pub(super) fn generate_token(public_id: &str) -> String {
+    let raw_token = TokenGenerator.generate();
+    format!("{TOKEN_PREFIX}{public_id}_{}", URL_SAFE.encode(&raw_token))
+}
+
+pub(super) fn digest_token(app_state: &AppState, token: &str) -> TokenDigest {
+    let keyring = app_state.secrets.api_key.keyring();
+    keyring.digest_token(token).unwrap()
 }
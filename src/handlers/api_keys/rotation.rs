@@ -0,0 +1,77 @@
+use chrono::{TimeDelta, Utc};
+use thiserror::Error;
+
+use super::registration::{RegistationDetails, digest_token, generate_token};
+use crate::{
+    AppState,
+    errors::{DatabaseError, PerRequestError},
+    models::{ApiKeyId, Timestamp, TokenGenerator, User},
+    repositories::RepositoryFactory,
+};
+
+#[derive(Debug, Error)]
+pub(super) enum RotationError {
+    #[error("database error")]
+    Database(#[from] DatabaseError),
+
+    #[error("key has expired")]
+    Expired,
+}
+
+impl From<RotationError> for PerRequestError {
+    fn from(value: RotationError) -> Self {
+        match value {
+            RotationError::Database(error) => error.into(),
+            RotationError::Expired => Self::BadRequest,
+        }
+    }
+}
+
+/// Issues a new token for an existing key. The replaced token stays valid for
+/// the configured grace period so that devices can be updated one by one.
+/// An expired key is not brought back to life by rotating it.
+pub(super) struct ApiKeyRotation<'a> {
+    app_state: &'a AppState,
+    user: &'a User,
+    id: &'a ApiKeyId,
+}
+
+impl<'a> ApiKeyRotation<'a> {
+    pub(super) fn new(app_state: &'a AppState, user: &'a User, id: &'a ApiKeyId) -> Self {
+        Self {
+            app_state,
+            user,
+            id,
+        }
+    }
+
+    pub(super) async fn execute(self) -> Result<RegistationDetails, RotationError> {
+        let repository = self.app_state.repositories.api_key();
+        let api_key = repository.find(self.user, self.id).await?;
+        let now = Utc::now();
+        if api_key.is_expired_at(&now.into()) {
+            return Err(RotationError::Expired);
+        }
+
+        // keys issued before tokens carried a public id get one now
+        let public_id = api_key
+            .public_id
+            .clone()
+            .unwrap_or_else(|| TokenGenerator.generate_public_id());
+        let token = generate_token(&public_id);
+        let digest = digest_token(self.app_state, &token);
+        let grace_period = TimeDelta::minutes(
+            self.app_state
+                .config
+                .api_keys
+                .rotation_grace_period_minutes
+                .into(),
+        );
+        let previous_digest_expires_at = Timestamp::from(now + grace_period);
+
+        let rotated = repository
+            .rotate(&api_key, &public_id, &digest, &previous_digest_expires_at)
+            .await?;
+        Ok(RegistationDetails::new(rotated, token))
+    }
+}
@@ -1,48 +1,115 @@
+use std::sync::Arc;
+
+use actix_multipart::Multipart;
 use actix_web::{
-    HttpResponse,
-    web::{Data, Form, Path, Query, ReqData, ServiceConfig, delete, get, post},
+    HttpRequest, HttpResponse,
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    web::{Data, Form, Path, Query, ReqData, ServiceConfig, delete, get, patch, post},
 };
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, NaiveDate};
+use futures_util::StreamExt;
 use serde::Deserialize;
 use serde_json::json;
 use validator::Validate;
 
-use super::views::{AttendanceRecordView, WorkplaceView};
+use super::attendance_registration::{AttendanceRegistration, ConflictPolicy, RegistrationOutcome};
+use super::period_lock::PeriodLock;
+use super::views::{
+    AttendanceRecordAuditView, AttendanceRecordView, WorkSessionView, WorkplaceView,
+};
 use crate::{
     AppState,
     errors::PerRequestError,
-    models::{AttendanceRecordId, User, WorkplaceId, attendance_record},
+    models::{
+        Actor, AttendanceRecordId, User, UserId, WorkSession, Workplace, WorkplaceId,
+        attendance_record,
+    },
     repositories::RepositoryFactory,
 };
 
+mod breakdown;
+mod export;
+mod import;
 mod listing;
-use listing::{AttendancesForMonth, TargetMonth};
+mod sequence;
+mod summary;
+use export::{DailyCsvExporter, ExportFormat};
+use import::{AttendanceImport, ImportError, ImportOutcome};
+use listing::AttendancesForPeriod;
+pub(super) use listing::{TargetMonth, TargetPeriod};
+use sequence::RecordSequence;
+use summary::PeriodSummarizer;
 
 pub(super) fn routes(config: &mut ServiceConfig) {
     config
         .route("", get().to(index))
         .route("", post().to(create))
-        .route("/{id}", delete().to(destroy));
+        .route("/import", post().to(import))
+        .route("/summary", get().to(summary))
+        .route("/trash", get().to(trash))
+        .route("/{id}", patch().to(update))
+        .route("/{id}", delete().to(destroy))
+        .route("/{id}/history", get().to(history))
+        .route("/{id}/restore", post().to(restore));
 }
 
 #[derive(Deserialize)]
-struct PathInfo {
+pub(super) struct PathInfo {
     workplace_id: WorkplaceId,
 }
 
 #[derive(Deserialize, Validate)]
-struct IndexParameters {
+pub(super) struct IndexParameters {
     #[validate(range(min = 0))]
     year: Option<i32>,
     #[validate(range(min = 1, max = 12))]
     month: Option<u32>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    week: Option<String>,
+    format: Option<ExportFormat>,
+    user_id: Option<UserId>,
 }
 
-async fn index(
+impl IndexParameters {
+    /// Summaries cover a single user, the viewer themselves unless another one is asked for.
+    fn summarized_user_id(&self, workplace: &Workplace) -> UserId {
+        self.user_id.unwrap_or(workplace.viewer.user_id)
+    }
+
+    /// The month shorthand applies unless `from`/`to` or `week` is given.
+    fn target_month(&self, workplace: &Workplace) -> Option<TargetMonth> {
+        if self.from.is_some() || self.to.is_some() || self.week.is_some() {
+            return None;
+        }
+        Some(TargetMonth::new(workplace, self.year, self.month))
+    }
+
+    fn target_period(&self, workplace: &Workplace) -> Result<TargetPeriod, PerRequestError> {
+        self.validate().map_err(|_| PerRequestError::BadRequest)?;
+        if let Some(target_month) = self.target_month(workplace) {
+            return target_month.period().ok_or(PerRequestError::BadRequest);
+        }
+        if self.year.is_some() || self.month.is_some() {
+            return Err(PerRequestError::BadRequest);
+        }
+
+        let timezone = *workplace.timezone;
+        let target_period = match (self.from, self.to, &self.week) {
+            (Some(from), Some(to), None) => TargetPeriod::between(timezone, from, to),
+            (None, None, Some(week)) => TargetPeriod::iso_week(timezone, week),
+            _ => None,
+        };
+        target_period.ok_or(PerRequestError::BadRequest)
+    }
+}
+
+pub(super) async fn index(
     app_state: Data<AppState>,
     current_user: ReqData<User>,
     path: Path<PathInfo>,
     params: Query<IndexParameters>,
+    request: HttpRequest,
 ) -> Result<HttpResponse, PerRequestError> {
     let workplace = app_state
         .repositories
@@ -50,15 +117,106 @@ async fn index(
         .find(&current_user, path.workplace_id)
         .await?;
 
-    let target_month = TargetMonth::new_with_default_timezone(params.year, params.month);
-    let finder = AttendancesForMonth::new(&app_state, &workplace, &target_month);
+    let target_month = params.target_month(&workplace);
+    let target_period = params.target_period(&workplace)?;
+    if ExportFormat::negotiate(params.format, &request) == ExportFormat::Csv {
+        return export_csv(
+            &app_state,
+            &workplace,
+            target_month.as_ref(),
+            &target_period,
+            params.summarized_user_id(&workplace),
+        )
+        .await;
+    }
+
+    let mut finder = AttendancesForPeriod::new(&app_state, &workplace, &target_period);
+    if let Some(user_id) = params.user_id {
+        finder = finder.of_user(user_id);
+    }
     let attendance_records = finder.execute().await?;
+    let work_sessions = WorkSession::pair(&attendance_records);
 
     let response_json = json!({
-        "year": &target_month.year,
-        "month": &target_month.month,
+        "year": target_month.as_ref().map(|target_month| &target_month.year),
+        "month": target_month.as_ref().map(|target_month| &target_month.month),
+        "from": target_period.first_date(),
+        "to": target_period.last_date(),
         "workplace": WorkplaceView::new(&workplace),
         "attendanceRecords": attendance_records.iter().map(AttendanceRecordView::new).collect::<Vec<AttendanceRecordView>>(),
+        "workSessions": work_sessions.iter().map(WorkSessionView::new).collect::<Vec<WorkSessionView>>(),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+async fn export_csv(
+    app_state: &AppState,
+    workplace: &Workplace,
+    target_month: Option<&TargetMonth>,
+    target_period: &TargetPeriod,
+    user_id: UserId,
+) -> Result<HttpResponse, PerRequestError> {
+    let finder = AttendancesForPeriod::new(app_state, workplace, target_period)
+        .including_leading_dates()
+        .of_user(user_id);
+    let attendance_records = finder.execute().await?;
+    let work_sessions = WorkSession::pair(&attendance_records);
+    let summary = PeriodSummarizer::new(target_period, &work_sessions, workplace).execute();
+    let body = DailyCsvExporter::new(target_period, &summary).execute()?;
+
+    let filename = match target_month {
+        Some(target_month) => format!(
+            "attendances-{}-{:02}.csv",
+            i32::from(target_month.year),
+            u32::from(target_month.month)
+        ),
+        None => format!(
+            "attendances-{}-{}.csv",
+            target_period.first_date(),
+            target_period.last_date()
+        ),
+    };
+    let response = HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(filename)],
+        })
+        .body(body);
+    Ok(response)
+}
+
+async fn summary(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+    params: Query<IndexParameters>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+
+    let target_month = params.target_month(&workplace);
+    let target_period = params.target_period(&workplace)?;
+    let user_id = params.summarized_user_id(&workplace);
+    let finder = AttendancesForPeriod::new(&app_state, &workplace, &target_period)
+        .including_leading_dates()
+        .of_user(user_id);
+    let attendance_records = finder.execute().await?;
+    let work_sessions = WorkSession::pair(&attendance_records);
+    let summary = PeriodSummarizer::new(&target_period, &work_sessions, &workplace).execute();
+
+    let response_json = json!({
+        "year": target_month.as_ref().map(|target_month| &target_month.year),
+        "month": target_month.as_ref().map(|target_month| &target_month.month),
+        "from": target_period.first_date(),
+        "to": target_period.last_date(),
+        "userId": user_id,
+        "workplace": WorkplaceView::new(&workplace),
+        "summary": summary,
     });
     let response = HttpResponse::Ok().json(response_json);
     Ok(response)
@@ -68,11 +226,14 @@ async fn index(
 struct CreationParameters {
     event: attendance_record::Event,
     datetime: DateTime<Local>,
+    #[serde(default)]
+    on_conflict: ConflictPolicy,
 }
 
 async fn create(
     app_state: Data<AppState>,
     current_user: ReqData<User>,
+    actor: ReqData<Actor>,
     path: Path<PathInfo>,
     form: Form<CreationParameters>,
 ) -> Result<HttpResponse, PerRequestError> {
@@ -82,10 +243,18 @@ async fn create(
         .find(&current_user, path.workplace_id)
         .await?;
 
-    let repository = app_state.repositories.attendance_record();
-    let attendance_record = repository
-        .create(&workplace, &form.event, &form.datetime.to_utc().into())
+    let registration = AttendanceRegistration::new(Arc::clone(&app_state.into_inner()));
+    let outcome = registration
+        .execute(
+            &actor,
+            &workplace,
+            form.event.clone(),
+            &form.datetime.to_utc().into(),
+            form.on_conflict,
+        )
         .await?;
+    let (RegistrationOutcome::Created(attendance_record)
+    | RegistrationOutcome::Merged(attendance_record)) = outcome;
 
     let response_json = json!({
         "attendanceRecord": AttendanceRecordView::new(&attendance_record),
@@ -95,15 +264,121 @@ async fn create(
 }
 
 #[derive(Deserialize)]
-struct DestroyPath {
+struct ImportParameters {
+    #[serde(default)]
+    dry_run: bool,
+}
+
+const IMPORT_FILE_LIMIT: usize = 1024 * 1024;
+
+async fn import(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    actor: ReqData<Actor>,
+    path: Path<PathInfo>,
+    params: Query<ImportParameters>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+
+    let mut csv = None;
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(|_| PerRequestError::BadRequest)?;
+        if field.name() == Some("file") {
+            let bytes = field
+                .bytes(IMPORT_FILE_LIMIT)
+                .await
+                .map_err(|_| PerRequestError::BadRequest)?
+                .map_err(|_| PerRequestError::BadRequest)?;
+            csv = Some(bytes);
+        }
+    }
+    let csv = csv.ok_or(PerRequestError::BadRequest)?;
+
+    let importer = AttendanceImport::new(&app_state, &actor, &workplace);
+    let response = match importer.execute(&csv, params.dry_run).await {
+        Ok(ImportOutcome::Planned(rows)) => HttpResponse::Ok().json(json!({
+            "dryRun": true,
+            "attendanceRecords": rows,
+        })),
+        Ok(ImportOutcome::Created(attendance_records)) => HttpResponse::Created().json(json!({
+            "dryRun": false,
+            "attendanceRecords": attendance_records.iter().map(AttendanceRecordView::new).collect::<Vec<AttendanceRecordView>>(),
+        })),
+        Err(ImportError::InvalidRows(errors)) => HttpResponse::UnprocessableEntity().json(json!({
+            "error": "invalid rows",
+            "errors": errors,
+        })),
+        Err(ImportError::Database(error)) => return Err(error.into()),
+    };
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+struct RecordPath {
     workplace_id: WorkplaceId,
     id: AttendanceRecordId,
 }
 
+#[derive(Deserialize)]
+struct UpdateParameters {
+    event: Option<attendance_record::Event>,
+    datetime: Option<DateTime<Local>>,
+}
+
+async fn update(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    actor: ReqData<Actor>,
+    path: Path<RecordPath>,
+    form: Form<UpdateParameters>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+
+    let repository = app_state.repositories.audited_attendance_record(&actor);
+    let original = repository.find(&workplace, path.id).await?;
+    let lock = PeriodLock::new(&app_state, &workplace);
+    lock.ensure_unlocked(original.user_id, &original.recorded_at)
+        .await?;
+    let mut attendance_record = original.clone();
+    if let Some(event) = &form.event {
+        attendance_record.event = event.clone();
+    }
+    if let Some(datetime) = &form.datetime {
+        attendance_record.recorded_at = datetime.to_utc().into();
+        lock.ensure_unlocked(attendance_record.user_id, &attendance_record.recorded_at)
+            .await?;
+    }
+    RecordSequence::new(&*repository, &workplace, original.user_id)
+        .excluding(original.id)
+        .ensure_movable(
+            &original,
+            &attendance_record.event,
+            &attendance_record.recorded_at,
+        )
+        .await?;
+    let attendance_record = repository.update(&workplace, &attendance_record).await?;
+
+    let response_json = json!({
+        "attendanceRecord": AttendanceRecordView::new(&attendance_record),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
 async fn destroy(
     app_state: Data<AppState>,
     current_user: ReqData<User>,
-    path: Path<DestroyPath>,
+    actor: ReqData<Actor>,
+    path: Path<RecordPath>,
 ) -> Result<HttpResponse, PerRequestError> {
     let workplace = app_state
         .repositories
@@ -111,9 +386,91 @@ async fn destroy(
         .find(&current_user, path.workplace_id)
         .await?;
 
-    let repository = app_state.repositories.attendance_record();
+    let repository = app_state.repositories.audited_attendance_record(&actor);
+    let attendance_record = repository.find(&workplace, path.id).await?;
+    PeriodLock::new(&app_state, &workplace)
+        .ensure_unlocked(attendance_record.user_id, &attendance_record.recorded_at)
+        .await?;
     repository.destroy(&workplace, path.id).await?;
 
     let response = HttpResponse::Ok().finish();
     Ok(response)
 }
+
+async fn trash(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+
+    let repository = app_state.repositories.attendance_record();
+    let attendance_records = repository.list_deleted(&workplace).await?;
+
+    let response_json = json!({
+        "workplace": WorkplaceView::new(&workplace),
+        "retentionDays": app_state.config.trash.retention_days,
+        "attendanceRecords": attendance_records.iter().map(AttendanceRecordView::new).collect::<Vec<AttendanceRecordView>>(),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+async fn restore(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    actor: ReqData<Actor>,
+    path: Path<RecordPath>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+
+    let repository = app_state.repositories.audited_attendance_record(&actor);
+    let deleted_record = repository.find_deleted(&workplace, path.id).await?;
+    PeriodLock::new(&app_state, &workplace)
+        .ensure_unlocked(deleted_record.user_id, &deleted_record.recorded_at)
+        .await?;
+    RecordSequence::new(&*repository, &workplace, deleted_record.user_id)
+        .ensure_insertable(&deleted_record.event, &deleted_record.recorded_at)
+        .await?;
+    let attendance_record = repository.restore(&workplace, path.id).await?;
+
+    let response_json = json!({
+        "attendanceRecord": AttendanceRecordView::new(&attendance_record),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+async fn history(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<RecordPath>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+
+    let repository = app_state.repositories.attendance_record_audit();
+    let audits = repository.list(&workplace, path.id).await?;
+    if audits.is_empty() {
+        // records made before the audit trail existed have no history
+        let repository = app_state.repositories.attendance_record();
+        repository.find(&workplace, path.id).await?;
+    }
+
+    let response_json = json!({
+        "history": audits.iter().map(AttendanceRecordAuditView::new).collect::<Vec<AttendanceRecordAuditView>>(),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
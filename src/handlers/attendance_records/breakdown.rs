@@ -0,0 +1,123 @@
+use std::iter::Sum;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::models::{OvertimeRules, WorkSession};
+
+/// Worked minutes of a day split by the premium they are paid with.
+/// Late-night minutes overlap with the other categories.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct WorkingTimeBreakdown {
+    regular_minutes: i64,
+    overtime_minutes: i64,
+    late_night_minutes: i64,
+    rest_day_minutes: i64,
+}
+
+impl<'a> Sum<&'a WorkingTimeBreakdown> for WorkingTimeBreakdown {
+    fn sum<I: Iterator<Item = &'a WorkingTimeBreakdown>>(iter: I) -> Self {
+        iter.fold(Self::default(), |total, breakdown| Self {
+            regular_minutes: total.regular_minutes + breakdown.regular_minutes,
+            overtime_minutes: total.overtime_minutes + breakdown.overtime_minutes,
+            late_night_minutes: total.late_night_minutes + breakdown.late_night_minutes,
+            rest_day_minutes: total.rest_day_minutes + breakdown.rest_day_minutes,
+        })
+    }
+}
+
+pub(super) struct BreakdownCalculator<'a> {
+    overtime_rules: &'a OvertimeRules,
+    timezone: Tz,
+    week_start: Option<NaiveDate>,
+    weekly_regular_minutes: i64,
+}
+
+impl<'a> BreakdownCalculator<'a> {
+    pub(super) fn new(overtime_rules: &'a OvertimeRules, timezone: Tz) -> Self {
+        Self {
+            overtime_rules,
+            timezone,
+            week_start: None,
+            weekly_regular_minutes: 0,
+        }
+    }
+
+    /// Days are expected in chronological order, since regular minutes are accumulated
+    /// through the week to find the weekly overtime.
+    /// Work on rest days is neither regular time nor overtime and does not count towards the week.
+    pub(super) fn calculate(
+        &mut self,
+        date: NaiveDate,
+        worked_minutes: i64,
+        sessions: &[&WorkSession],
+    ) -> WorkingTimeBreakdown {
+        let week_start = date.week(Weekday::Sun).first_day();
+        if self.week_start != Some(week_start) {
+            self.week_start = Some(week_start);
+            self.weekly_regular_minutes = 0;
+        }
+
+        let late_night_minutes = sessions
+            .iter()
+            .map(|session| self.late_night_duration(session))
+            .sum::<Duration>()
+            .num_minutes();
+
+        if self.overtime_rules.rest_days.contains(date.weekday()) {
+            return WorkingTimeBreakdown {
+                late_night_minutes,
+                rest_day_minutes: worked_minutes,
+                ..WorkingTimeBreakdown::default()
+            };
+        }
+
+        let daily_overtime = (worked_minutes - self.overtime_rules.daily_threshold_minutes).max(0);
+        let regular = worked_minutes - daily_overtime;
+        let weekly_overtime = (self.weekly_regular_minutes + regular
+            - self.overtime_rules.weekly_threshold_minutes)
+            .clamp(0, regular);
+        self.weekly_regular_minutes += regular - weekly_overtime;
+
+        WorkingTimeBreakdown {
+            regular_minutes: regular - weekly_overtime,
+            overtime_minutes: daily_overtime + weekly_overtime,
+            late_night_minutes,
+            rest_day_minutes: 0,
+        }
+    }
+
+    // Late night is 22:00 to 05:00 local time as defined by the Labor Standards Act.
+    // Breaks deducted by the break rules are not attributed to any time of the day,
+    // so only recorded breaks are cut out here.
+    fn late_night_duration(&self, session: &WorkSession) -> Duration {
+        session
+            .worked_periods()
+            .iter()
+            .map(|(start, end)| {
+                let first_date = start.with_timezone(&self.timezone).date_naive().pred_opt();
+                let last_date = end.with_timezone(&self.timezone).date_naive();
+                first_date
+                    .into_iter()
+                    .flat_map(|date| date.iter_days())
+                    .take_while(|date| *date <= last_date)
+                    .filter_map(|date| {
+                        let window_start = self.to_utc(date.and_hms_opt(22, 0, 0)?)?;
+                        let window_end = self.to_utc(date.succ_opt()?.and_hms_opt(5, 0, 0)?)?;
+                        let overlap = (*end).min(window_end) - (*start).max(window_start);
+                        Some(overlap.max(Duration::zero()))
+                    })
+                    .sum::<Duration>()
+            })
+            .sum()
+    }
+
+    fn to_utc(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        local
+            .and_local_timezone(self.timezone)
+            .earliest()
+            .map(|datetime| datetime.to_utc())
+    }
+}
@@ -0,0 +1,84 @@
+use actix_web::{HttpRequest, http::header::ACCEPT};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{listing::TargetPeriod, summary::PeriodSummary};
+use crate::models::Timestamp;
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    /// The `format` query parameter takes precedence over the `Accept` header.
+    pub(super) fn negotiate(format: Option<Self>, request: &HttpRequest) -> Self {
+        if let Some(format) = format {
+            return format;
+        }
+
+        let accepts_csv = request
+            .headers()
+            .get_all(ACCEPT)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|media_type| media_type.trim().starts_with("text/csv"));
+        if accepts_csv { Self::Csv } else { Self::Json }
+    }
+}
+
+#[derive(Serialize)]
+struct CsvRow {
+    date: NaiveDate,
+    clock_in: Option<String>,
+    clock_out: Option<String>,
+    break_hours: String,
+    worked_hours: String,
+}
+
+/// Writes one row per day of the period, with times in the workplace timezone.
+/// Breaks include the time deducted by the break rules, so that they add up with worked hours.
+pub(super) struct DailyCsvExporter<'a> {
+    target_period: &'a TargetPeriod,
+    summary: &'a PeriodSummary,
+}
+
+impl<'a> DailyCsvExporter<'a> {
+    pub(super) fn new(target_period: &'a TargetPeriod, summary: &'a PeriodSummary) -> Self {
+        Self {
+            target_period,
+            summary,
+        }
+    }
+
+    pub(super) fn execute(self) -> Result<Vec<u8>, csv::Error> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for day in &self.summary.days {
+            writer.serialize(CsvRow {
+                date: day.date,
+                clock_in: day.first_clock_in.as_ref().map(|t| self.local_time(t)),
+                clock_out: day.last_clock_out.as_ref().map(|t| self.local_time(t)),
+                break_hours: hours(day.break_minutes + day.deducted_break_minutes),
+                worked_hours: hours(day.worked_minutes),
+            })?;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error().into())
+            .inspect_err(|e| log::error!("Failed to export attendances: {:?}", e))
+    }
+
+    fn local_time(&self, timestamp: &Timestamp) -> String {
+        timestamp
+            .with_timezone(&self.target_period.timezone())
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    }
+}
+
+fn hours(minutes: i64) -> String {
+    format!("{:.2}", minutes as f64 / 60.0)
+}
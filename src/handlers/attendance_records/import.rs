@@ -0,0 +1,253 @@
+use chrono::{DateTime, NaiveDateTime, TimeDelta};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize, de::IntoDeserializer};
+use thiserror::Error;
+
+use crate::{
+    AppState,
+    errors::DatabaseError,
+    handlers::period_lock::PeriodLock,
+    models::{
+        Actor, AttendanceRecord, Timestamp, Workplace,
+        attendance_record::{AttendanceState, Event},
+    },
+    repositories::RepositoryFactory,
+};
+
+const LOCAL_DATETIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+];
+
+#[derive(Debug, Serialize)]
+pub(super) struct LineError {
+    line: u64,
+    reason: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ImportRow {
+    line: u64,
+    event: Event,
+    recorded_at: Timestamp,
+}
+
+#[derive(Deserialize)]
+struct CsvRow {
+    event: String,
+    datetime: String,
+}
+
+pub(super) enum ImportOutcome {
+    Planned(Vec<ImportRow>),
+    Created(Vec<AttendanceRecord>),
+}
+
+#[derive(Debug, Error)]
+pub(super) enum ImportError {
+    #[error("database error")]
+    Database(#[from] DatabaseError),
+
+    #[error("invalid rows")]
+    InvalidRows(Vec<LineError>),
+}
+
+/// Imports a CSV file with `event` and `datetime` columns into a workplace.
+/// Datetimes without an offset are read in the workplace timezone.
+/// Nothing is written unless every row is valid, and nothing at all on a dry run.
+pub(super) struct AttendanceImport<'a> {
+    app_state: &'a AppState,
+    actor: &'a Actor,
+    workplace: &'a Workplace,
+}
+
+impl<'a> AttendanceImport<'a> {
+    pub(super) fn new(app_state: &'a AppState, actor: &'a Actor, workplace: &'a Workplace) -> Self {
+        Self {
+            app_state,
+            actor,
+            workplace,
+        }
+    }
+
+    pub(super) async fn execute(
+        self,
+        csv: &[u8],
+        dry_run: bool,
+    ) -> Result<ImportOutcome, ImportError> {
+        let rows = self.parse(csv).map_err(ImportError::InvalidRows)?;
+        let mut errors = self.check_locks(&rows).await?;
+        errors.extend(self.check_sequence(&rows).await?);
+        errors.sort_by_key(|error| error.line);
+        if !errors.is_empty() {
+            return Err(ImportError::InvalidRows(errors));
+        }
+
+        if dry_run {
+            return Ok(ImportOutcome::Planned(rows));
+        }
+        let attendance_records = self.insert(&rows).await?;
+        Ok(ImportOutcome::Created(attendance_records))
+    }
+
+    fn parse(&self, csv: &[u8]) -> Result<Vec<ImportRow>, Vec<LineError>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(csv);
+        let headers = reader.headers().cloned().map_err(|_| {
+            vec![LineError {
+                line: 1,
+                reason: "malformed-header",
+            }]
+        })?;
+
+        let mut rows = Vec::new();
+        let mut errors = Vec::new();
+        for result in reader.records() {
+            let row = result
+                .map_err(|e| LineError {
+                    line: e.position().map_or(0, |position| position.line()),
+                    reason: "malformed-row",
+                })
+                .and_then(|record| {
+                    let line = record.position().map_or(0, |position| position.line());
+                    record
+                        .deserialize::<CsvRow>(Some(&headers))
+                        .map_err(|_| LineError {
+                            line,
+                            reason: "malformed-row",
+                        })
+                        .and_then(|row| self.parse_row(line, row))
+                });
+            match row {
+                Ok(row) => rows.push(row),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        if rows.is_empty() {
+            return Err(vec![LineError {
+                line: 1,
+                reason: "no-rows",
+            }]);
+        }
+        // A stable sort keeps the order of the file for rows at the same time.
+        rows.sort_by(|a, b| a.recorded_at.cmp(&b.recorded_at));
+        Ok(rows)
+    }
+
+    fn parse_row(&self, line: u64, row: CsvRow) -> Result<ImportRow, LineError> {
+        let event = Event::deserialize(row.event.as_str().into_deserializer()).map_err(
+            |_: serde::de::value::Error| LineError {
+                line,
+                reason: "invalid-event",
+            },
+        )?;
+        let recorded_at =
+            parse_datetime(&row.datetime, *self.workplace.timezone).ok_or(LineError {
+                line,
+                reason: "invalid-datetime",
+            })?;
+
+        Ok(ImportRow {
+            line,
+            event,
+            recorded_at,
+        })
+    }
+
+    async fn check_locks(&self, rows: &[ImportRow]) -> Result<Vec<LineError>, DatabaseError> {
+        let lock = PeriodLock::new(self.app_state, self.workplace);
+        let mut errors = Vec::new();
+        for row in rows {
+            if lock
+                .is_locked(self.workplace.viewer.user_id, &row.recorded_at)
+                .await?
+            {
+                errors.push(LineError {
+                    line: row.line,
+                    reason: "period-locked",
+                });
+            }
+        }
+        Ok(errors)
+    }
+
+    // Checks the rows the same way as a single registration, with the existing records
+    // in between taken into account.
+    async fn check_sequence(&self, rows: &[ImportRow]) -> Result<Vec<LineError>, DatabaseError> {
+        let repository = self.app_state.repositories.attendance_record();
+        let (first, last) = (&rows[0].recorded_at, &rows[rows.len() - 1].recorded_at);
+        let previous = repository
+            .find_previous(self.workplace, self.workplace.viewer.user_id, first, None)
+            .await?;
+        let end = (**last + TimeDelta::seconds(1)).into();
+        let existing_records = repository.list(self.workplace, first, &end).await?;
+
+        let mut state = AttendanceState::after(previous.as_ref());
+        let mut existing_records = existing_records
+            .iter()
+            .filter(|record| record.user_id == self.workplace.viewer.user_id)
+            .peekable();
+        let mut errors = Vec::new();
+        for row in rows {
+            while let Some(record) =
+                existing_records.next_if(|record| record.recorded_at <= row.recorded_at)
+            {
+                state = AttendanceState::after_event(&record.event);
+            }
+            state = match state.transition(&row.event) {
+                Ok(state) => state,
+                Err(error) => {
+                    errors.push(LineError {
+                        line: row.line,
+                        reason: error.reason(),
+                    });
+                    AttendanceState::after_event(&row.event)
+                }
+            };
+        }
+
+        // The record following the import has to be accepted after the last row.
+        let next = repository
+            .find_next(self.workplace, self.workplace.viewer.user_id, last, None)
+            .await?;
+        if let Some(Err(error)) = next.map(|next| state.transition(&next.event)) {
+            errors.push(LineError {
+                line: rows[rows.len() - 1].line,
+                reason: error.reason(),
+            });
+        }
+        Ok(errors)
+    }
+
+    async fn insert(&self, rows: &[ImportRow]) -> Result<Vec<AttendanceRecord>, DatabaseError> {
+        let entries = rows
+            .iter()
+            .map(|row| (row.event.clone(), row.recorded_at.clone()))
+            .collect::<Vec<(Event, Timestamp)>>();
+        let repository = self
+            .app_state
+            .repositories
+            .audited_attendance_record(self.actor);
+        repository.create_many(self.workplace, &entries).await
+    }
+}
+
+fn parse_datetime(value: &str, timezone: Tz) -> Option<Timestamp> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.to_utc().into());
+    }
+
+    LOCAL_DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .and_then(|local| local.and_local_timezone(timezone).single())
+        .map(|datetime| datetime.to_utc().into())
+}
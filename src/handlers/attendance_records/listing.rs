@@ -1,11 +1,11 @@
-use chrono::{Datelike, Months, NaiveDate, Utc};
-use chrono_tz::{Asia, Tz};
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, NaiveTime, TimeDelta, Utc, Weekday};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     AppState,
     errors::DatabaseError,
-    models::{AttendanceRecord, Timestamp, Workplace},
+    models::{AttendanceRecord, ClosingDay, Timestamp, UserId, Workplace},
     repositories::RepositoryFactory,
 };
 
@@ -29,69 +29,210 @@ impl From<Month> for u32 {
     }
 }
 
-pub(super) struct TargetMonth {
+/// A payroll month, which ends on the closing day of the workplace.
+/// With the 20th as the closing day, January runs from December 21st through January 20th.
+pub(in crate::handlers) struct TargetMonth {
     pub(super) year: Year,
     pub(super) month: Month,
 
     timezone: Tz,
+    closing_day: ClosingDay,
 }
 
 impl TargetMonth {
-    pub(super) fn new_with_default_timezone(year_opt: Option<i32>, month_opt: Option<u32>) -> Self {
-        let timezone = Asia::Tokyo;
-        let now = Utc::now().with_timezone(&timezone);
-
-        let year = year_opt.unwrap_or(now.year());
-        let month = month_opt.unwrap_or(now.month());
+    /// Defaults to the payroll month which contains today.
+    pub(in crate::handlers) fn new(
+        workplace: &Workplace,
+        year_opt: Option<i32>,
+        month_opt: Option<u32>,
+    ) -> Self {
+        let timezone = *workplace.timezone;
+        let closing_day = workplace.closing_day;
+
+        let today = Utc::now().with_timezone(&timezone).date_naive();
+        let current_month_start = today.with_day(1).unwrap();
+        let current_month_start = if closing_date(current_month_start, closing_day)
+            .is_some_and(|closing_date| today > closing_date)
+        {
+            current_month_start
+                .checked_add_months(Months::new(1))
+                .unwrap_or(current_month_start)
+        } else {
+            current_month_start
+        };
+
+        let year = year_opt.unwrap_or(current_month_start.year());
+        let month = month_opt.unwrap_or(current_month_start.month());
 
         Self {
             year: Year(year),
             month: Month(month),
 
             timezone,
+            closing_day,
         }
     }
 
-    fn datetime_range(&self) -> (Timestamp, Timestamp) {
-        let timezone = self.timezone;
+    /// Returns `None` when the year or month is out of the range of dates.
+    pub(in crate::handlers) fn period(&self) -> Option<TargetPeriod> {
+        let month_start = NaiveDate::from_ymd_opt(self.year.into(), self.month.into(), 1)?;
+        let previous_month_start = month_start.checked_sub_months(Months::new(1))?;
+
+        let first_date = closing_date(previous_month_start, self.closing_day)?.succ_opt()?;
+        let last_date = closing_date(month_start, self.closing_day)?;
+        TargetPeriod::between(self.timezone, first_date, last_date)
+    }
+}
 
-        let local_start_time = NaiveDate::from_ymd_opt(self.year.into(), self.month.into(), 1)
-            .unwrap()
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(timezone)
-            .unwrap();
-        let local_end_time = local_start_time.checked_add_months(Months::new(1)).unwrap();
+// The closing date in the month starting at `month_start`, clamped to the end of the month.
+fn closing_date(month_start: NaiveDate, closing_day: ClosingDay) -> Option<NaiveDate> {
+    let month_end = month_start.checked_add_months(Months::new(1))?.pred_opt()?;
+    let closing_date = month_start
+        .with_day(closing_day.day())
+        .filter(|date| *date <= month_end)
+        .unwrap_or(month_end);
+    Some(closing_date)
+}
+
+/// Consecutive local dates in a workplace timezone, both ends inclusive.
+pub(in crate::handlers) struct TargetPeriod {
+    timezone: Tz,
+    first_date: NaiveDate,
+    last_date: NaiveDate,
+    end_date: NaiveDate,
+}
 
-        let utc_start_time = local_start_time.to_utc().into();
-        let utc_end_time = local_end_time.to_utc().into();
+impl TargetPeriod {
+    const MAX_DAYS: i64 = 366;
+
+    /// Returns `None` unless `from` is on or before `to` and the period is at most a year long.
+    /// Dates at the very ends of the calendar are refused, as the leading week or the day after
+    /// the period would not exist.
+    pub(super) fn between(timezone: Tz, from: NaiveDate, to: NaiveDate) -> Option<Self> {
+        let days = (to - from).num_days() + 1;
+        if !(1..=Self::MAX_DAYS).contains(&days) {
+            return None;
+        }
+        from.checked_sub_days(Days::new(6))?;
+        let end_date = to.succ_opt()?;
+        Some(Self {
+            timezone,
+            first_date: from,
+            last_date: to,
+            end_date,
+        })
+    }
+
+    /// Monday through Sunday of an ISO 8601 week such as `2026-W05`.
+    pub(super) fn iso_week(timezone: Tz, week: &str) -> Option<Self> {
+        let (year, week) = week.split_once("-W")?;
+        let monday =
+            NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon)?;
+        let sunday = monday.checked_add_days(Days::new(6))?;
+        Self::between(timezone, monday, sunday)
+    }
+
+    pub(super) fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    pub(super) fn first_date(&self) -> NaiveDate {
+        self.first_date
+    }
+
+    pub(super) fn last_date(&self) -> NaiveDate {
+        self.last_date
+    }
+
+    pub(super) fn dates(&self) -> Vec<NaiveDate> {
+        self.first_date
+            .iter_days()
+            .take_while(|date| *date <= self.last_date)
+            .collect()
+    }
+
+    /// Dates from the Sunday starting the week of the first date, up to the first date.
+    pub(super) fn leading_dates(&self) -> Vec<NaiveDate> {
+        let first_date = self.first_date;
+        first_date
+            .week(Weekday::Sun)
+            .first_day()
+            .iter_days()
+            .take_while(|date| *date < first_date)
+            .collect()
+    }
+
+    pub(in crate::handlers) fn datetime_range(
+        &self,
+        including_leading_dates: bool,
+    ) -> (Timestamp, Timestamp) {
+        let first_date = match self.leading_dates().first() {
+            Some(date) if including_leading_dates => *date,
+            _ => self.first_date,
+        };
+        let utc_start_time = self.local_start_of(first_date).to_utc().into();
+        let utc_end_time = self.local_start_of(self.end_date).to_utc().into();
         (utc_start_time, utc_end_time)
     }
+
+    /// The first instant of `date`. Where a DST transition skips local midnight,
+    /// the day starts at the first local time that exists.
+    fn local_start_of(&self, date: NaiveDate) -> DateTime<Tz> {
+        let midnight = date.and_time(NaiveTime::MIN);
+        (0..24 * 60)
+            .find_map(|minutes| {
+                (midnight + TimeDelta::minutes(minutes))
+                    .and_local_timezone(self.timezone)
+                    .earliest()
+            })
+            .unwrap_or_else(|| midnight.and_utc().with_timezone(&self.timezone))
+    }
 }
 
-pub(super) struct AttendancesForMonth<'a> {
+pub(super) struct AttendancesForPeriod<'a> {
     app_state: &'a AppState,
     workplace: &'a Workplace,
-    target_month: &'a TargetMonth,
+    target_period: &'a TargetPeriod,
+    including_leading_dates: bool,
+    user_id: Option<UserId>,
 }
 
-impl<'a> AttendancesForMonth<'a> {
+impl<'a> AttendancesForPeriod<'a> {
     pub(super) fn new(
         app_state: &'a AppState,
         workplace: &'a Workplace,
-        target_month: &'a TargetMonth,
+        target_period: &'a TargetPeriod,
     ) -> Self {
         Self {
             app_state,
             workplace,
-            target_month,
+            target_period,
+            including_leading_dates: false,
+            user_id: None,
         }
     }
 
+    /// Also fetches the records of `TargetPeriod::leading_dates`.
+    pub(super) fn including_leading_dates(mut self) -> Self {
+        self.including_leading_dates = true;
+        self
+    }
+
+    /// Narrows the records the viewer may see down to those of a single user.
+    pub(super) fn of_user(mut self, user_id: UserId) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
     pub(super) async fn execute(self) -> Result<Vec<AttendanceRecord>, DatabaseError> {
-        let (start, end) = self.target_month.datetime_range();
+        let (start, end) = self
+            .target_period
+            .datetime_range(self.including_leading_dates);
         let repository = self.app_state.repositories.attendance_record();
-        let attendance_records = repository.list(self.workplace, &start, &end).await?;
+        let mut attendance_records = repository.list(self.workplace, &start, &end).await?;
+        if let Some(user_id) = self.user_id {
+            attendance_records.retain(|record| record.user_id == user_id);
+        }
         Ok(attendance_records)
     }
 }
@@ -0,0 +1,112 @@
+use crate::{
+    errors::PerRequestError,
+    models::{
+        AttendanceRecord, AttendanceRecordId, Timestamp, UserId, Workplace,
+        attendance_record::{AttendanceState, Event, TransitionError},
+    },
+    repositories::AttendanceRecordRepository,
+};
+
+/// Keeps the records of a user in a workplace a valid sequence of events. As the state only
+/// depends on the latest event, a record placed or taken out only affects its neighbours.
+pub(super) struct RecordSequence<'a> {
+    repository: &'a dyn AttendanceRecordRepository,
+    workplace: &'a Workplace,
+    user_id: UserId,
+    excluding: Option<AttendanceRecordId>,
+}
+
+impl<'a> RecordSequence<'a> {
+    pub(super) fn new(
+        repository: &'a dyn AttendanceRecordRepository,
+        workplace: &'a Workplace,
+        user_id: UserId,
+    ) -> Self {
+        Self {
+            repository,
+            workplace,
+            user_id,
+            excluding: None,
+        }
+    }
+
+    /// Looks at the sequence as if the record `id` were not in it, as when moving it.
+    pub(super) fn excluding(mut self, id: AttendanceRecordId) -> Self {
+        self.excluding = Some(id);
+        self
+    }
+
+    /// `event` at `datetime` must be accepted after the record before it, and the record after
+    /// it must still be accepted in turn.
+    pub(super) async fn ensure_insertable(
+        &self,
+        event: &Event,
+        datetime: &Timestamp,
+    ) -> Result<(), PerRequestError> {
+        let (previous, next) = self.neighbours(datetime).await?;
+        Self::ensure_accepted(previous.as_ref(), event, next.as_ref())
+    }
+
+    /// Moves `original` to carry `event` at `datetime`. The records around the new position must
+    /// accept it, and unless it stays between the same records, those around its old position
+    /// become adjacent and must fit each other.
+    pub(super) async fn ensure_movable(
+        &self,
+        original: &AttendanceRecord,
+        event: &Event,
+        datetime: &Timestamp,
+    ) -> Result<(), PerRequestError> {
+        let (new_previous, new_next) = self.neighbours(datetime).await?;
+        Self::ensure_accepted(new_previous.as_ref(), event, new_next.as_ref())?;
+
+        let (old_previous, old_next) = self.neighbours(&original.recorded_at).await?;
+        let stays =
+            id_of(&old_previous) == id_of(&new_previous) && id_of(&old_next) == id_of(&new_next);
+        match &old_next {
+            Some(old_next) if !stays => {
+                AttendanceState::after(old_previous.as_ref())
+                    .transition(&old_next.event)
+                    .map_err(conflict)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn neighbours(
+        &self,
+        datetime: &Timestamp,
+    ) -> Result<(Option<AttendanceRecord>, Option<AttendanceRecord>), PerRequestError> {
+        let previous = self
+            .repository
+            .find_previous(self.workplace, self.user_id, datetime, self.excluding)
+            .await?;
+        let next = self
+            .repository
+            .find_next(self.workplace, self.user_id, datetime, self.excluding)
+            .await?;
+        Ok((previous, next))
+    }
+
+    fn ensure_accepted(
+        previous: Option<&AttendanceRecord>,
+        event: &Event,
+        next: Option<&AttendanceRecord>,
+    ) -> Result<(), PerRequestError> {
+        let state = AttendanceState::after(previous)
+            .transition(event)
+            .map_err(conflict)?;
+        if let Some(next) = next {
+            state.transition(&next.event).map_err(conflict)?;
+        }
+        Ok(())
+    }
+}
+
+fn conflict(error: TransitionError) -> PerRequestError {
+    PerRequestError::Conflict(error.reason())
+}
+
+fn id_of(record: &Option<AttendanceRecord>) -> Option<AttendanceRecordId> {
+    record.as_ref().map(|record| record.id)
+}
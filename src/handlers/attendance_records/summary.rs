@@ -0,0 +1,154 @@
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use super::{
+    breakdown::{BreakdownCalculator, WorkingTimeBreakdown},
+    listing::TargetPeriod,
+};
+use crate::models::{Timestamp, WorkSession, Workplace};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct DailySummary {
+    pub(super) date: NaiveDate,
+    pub(super) first_clock_in: Option<Timestamp>,
+    pub(super) last_clock_out: Option<Timestamp>,
+    pub(super) break_minutes: i64,
+    pub(super) deducted_break_minutes: i64,
+    pub(super) worked_minutes: i64,
+    session_count: usize,
+    auto_adjusted: bool,
+    breakdown: WorkingTimeBreakdown,
+}
+
+impl DailySummary {
+    fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            first_clock_in: None,
+            last_clock_out: None,
+            break_minutes: 0,
+            deducted_break_minutes: 0,
+            worked_minutes: 0,
+            session_count: 0,
+            auto_adjusted: false,
+            breakdown: WorkingTimeBreakdown::default(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PeriodTotal {
+    break_minutes: i64,
+    deducted_break_minutes: i64,
+    worked_minutes: i64,
+    session_count: usize,
+    working_days: usize,
+    auto_adjusted_days: usize,
+    breakdown: WorkingTimeBreakdown,
+}
+
+#[derive(Serialize)]
+pub(super) struct PeriodSummary {
+    pub(super) days: Vec<DailySummary>,
+    total: PeriodTotal,
+}
+
+pub(super) struct PeriodSummarizer<'a> {
+    target_period: &'a TargetPeriod,
+    work_sessions: &'a [WorkSession],
+    workplace: &'a Workplace,
+}
+
+impl<'a> PeriodSummarizer<'a> {
+    pub(super) fn new(
+        target_period: &'a TargetPeriod,
+        work_sessions: &'a [WorkSession],
+        workplace: &'a Workplace,
+    ) -> Self {
+        Self {
+            target_period,
+            work_sessions,
+            workplace,
+        }
+    }
+
+    /// `work_sessions` should include the days of `TargetPeriod::leading_dates`,
+    /// which count towards the weekly overtime of the first week.
+    pub(super) fn execute(self) -> PeriodSummary {
+        let mut calculator = BreakdownCalculator::new(
+            &self.workplace.overtime_rules,
+            self.target_period.timezone(),
+        );
+        for date in self.target_period.leading_dates() {
+            self.summarize_day(date, &mut calculator);
+        }
+        let days = self
+            .target_period
+            .dates()
+            .into_iter()
+            .map(|date| self.summarize_day(date, &mut calculator))
+            .collect::<Vec<DailySummary>>();
+
+        let total = PeriodTotal {
+            break_minutes: days.iter().map(|day| day.break_minutes).sum(),
+            deducted_break_minutes: days.iter().map(|day| day.deducted_break_minutes).sum(),
+            worked_minutes: days.iter().map(|day| day.worked_minutes).sum(),
+            session_count: days.iter().map(|day| day.session_count).sum(),
+            working_days: days.iter().filter(|day| day.session_count > 0).count(),
+            auto_adjusted_days: days.iter().filter(|day| day.auto_adjusted).count(),
+            breakdown: days.iter().map(|day| &day.breakdown).sum(),
+        };
+        PeriodSummary { days, total }
+    }
+
+    fn summarize_day(&self, date: NaiveDate, calculator: &mut BreakdownCalculator) -> DailySummary {
+        let sessions = self
+            .work_sessions
+            .iter()
+            .filter(|session| self.local_date(session) == date)
+            .collect::<Vec<&WorkSession>>();
+
+        let mut summary = DailySummary::new(date);
+        summary.first_clock_in = sessions
+            .iter()
+            .filter_map(|session| session.started_at())
+            .min()
+            .cloned();
+        summary.last_clock_out = sessions
+            .iter()
+            .filter_map(|session| session.ended_at())
+            .max()
+            .cloned();
+        let recorded_break: Duration = sessions
+            .iter()
+            .filter(|session| session.duration().is_some())
+            .map(|session| session.break_duration())
+            .sum();
+        let worked: Duration = sessions
+            .iter()
+            .filter_map(|session| session.worked_duration())
+            .sum();
+
+        // Raw records stay untouched; a break the rules require but nobody recorded is only
+        // deducted here.
+        let missing_break = (self.workplace.break_rules.required_break(worked) - recorded_break)
+            .clamp(Duration::zero(), worked);
+        summary.break_minutes = recorded_break.num_minutes();
+        summary.deducted_break_minutes = missing_break.num_minutes();
+        summary.worked_minutes = (worked - missing_break).num_minutes();
+        summary.session_count = sessions.len();
+        summary.auto_adjusted = missing_break > Duration::zero();
+        summary.breakdown = calculator.calculate(date, summary.worked_minutes, &sessions);
+        summary
+    }
+
+    // A session belongs to the local day it started on, so that a night shift is not split in two.
+    fn local_date(&self, session: &WorkSession) -> NaiveDate {
+        let timestamp = session.started_at().or(session.ended_at()).unwrap();
+        timestamp
+            .with_timezone(&self.target_period.timezone())
+            .date_naive()
+    }
+}
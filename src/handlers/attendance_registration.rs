@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    AppState,
+    errors::{DatabaseError, PerRequestError},
+    models::{
+        Actor, AttendanceRecord, Timestamp, Workplace,
+        attendance_record::{Event, PlacementError, TransitionError},
+    },
+    repositories::RepositoryFactory,
+};
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(in crate::handlers) enum ConflictPolicy {
+    #[default]
+    Reject,
+    Merge,
+}
+
+pub(in crate::handlers) enum RegistrationOutcome {
+    Created(AttendanceRecord),
+    Merged(AttendanceRecord),
+}
+
+#[derive(Debug, Error)]
+pub(in crate::handlers) enum RegistrationError {
+    #[error("database error")]
+    Database(#[from] DatabaseError),
+
+    #[error("invalid event sequence")]
+    InvalidSequence(TransitionError),
+
+    #[error("period locked")]
+    Locked,
+}
+
+impl From<RegistrationError> for PerRequestError {
+    fn from(value: RegistrationError) -> Self {
+        match value {
+            RegistrationError::Database(error) => error.into(),
+            RegistrationError::InvalidSequence(error) => Self::Conflict(error.reason()),
+            RegistrationError::Locked => Self::Conflict("period-locked"),
+        }
+    }
+}
+
+pub(in crate::handlers) struct AttendanceRegistration {
+    app_state: Arc<AppState>,
+}
+
+impl AttendanceRegistration {
+    pub(in crate::handlers) fn new(app_state: Arc<AppState>) -> Self {
+        Self { app_state }
+    }
+
+    /// Records `event` at `datetime` unless the state at that moment, which is derived from
+    /// the record right before it, does not accept the event, the record right after it would
+    /// no longer be accepted, or an approved timesheet locks it.
+    /// With `ConflictPolicy::Merge` a conflicting record of the same event is returned
+    /// instead of an error.
+    pub(in crate::handlers) async fn execute(
+        &self,
+        actor: &Actor,
+        workplace: &Workplace,
+        event: Event,
+        datetime: &Timestamp,
+        policy: ConflictPolicy,
+    ) -> Result<RegistrationOutcome, RegistrationError> {
+        let repository = self.app_state.repositories.audited_attendance_record(actor);
+        match repository
+            .create_in_sequence(workplace, &event, datetime)
+            .await?
+        {
+            Ok(attendance_record) => Ok(RegistrationOutcome::Created(attendance_record)),
+            Err(PlacementError::Locked) => Err(RegistrationError::Locked),
+            Err(PlacementError::NotAccepted(error, previous)) => {
+                let same_event = previous.filter(|record| record.event == event);
+                Self::resolve_conflict(error, same_event, policy)
+            }
+            Err(PlacementError::BreaksNext(error)) => {
+                Err(RegistrationError::InvalidSequence(error))
+            }
+        }
+    }
+
+    fn resolve_conflict(
+        error: TransitionError,
+        conflicting_record: Option<AttendanceRecord>,
+        policy: ConflictPolicy,
+    ) -> Result<RegistrationOutcome, RegistrationError> {
+        match (policy, conflicting_record) {
+            (ConflictPolicy::Merge, Some(record)) => Ok(RegistrationOutcome::Merged(record)),
+            _ => Err(RegistrationError::InvalidSequence(error)),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use actix_web::{
+    HttpResponse,
+    web::{Data, Path, ReqData, ServiceConfig, delete, get, post},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE};
+use chrono::{TimeDelta, Utc};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::views::CalendarFeedView;
+use crate::{
+    AppState,
+    errors::PerRequestError,
+    models::{CalendarFeed, TokenGenerator, User, WorkSession, WorkplaceId},
+    repositories::RepositoryFactory,
+};
+
+mod icalendar;
+use icalendar::WorkSessionCalendar;
+
+const FEED_PERIOD_DAYS: i64 = 90;
+
+pub(super) fn routes(config: &mut ServiceConfig) {
+    config
+        .route("", get().to(show))
+        .route("", post().to(create))
+        .route("", delete().to(destroy));
+}
+
+/// Routes for calendar clients, which authenticate with the feed token in the path.
+pub(super) fn feed_routes(config: &mut ServiceConfig) {
+    config.route("/{token}.ics", get().to(feed));
+}
+
+#[derive(Deserialize)]
+struct PathInfo {
+    workplace_id: WorkplaceId,
+}
+
+async fn show(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+    let calendar_feed = app_state
+        .repositories
+        .calendar_feed()
+        .find(&workplace)
+        .await?
+        .ok_or(PerRequestError::NotFound)?;
+
+    let response_json = json!({
+        "calendarFeed": CalendarFeedView::new(&calendar_feed),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+async fn create(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+
+    let token = URL_SAFE.encode(TokenGenerator.generate());
+    let keyring = app_state.secrets.api_key.keyring();
+    let digest = keyring.digest_token(&token)?;
+    let repository = app_state.repositories.calendar_feed();
+    let calendar_feed = repository.create(&workplace, &digest.value).await?;
+
+    let response_json = json!({
+        "calendarFeed": CalendarFeedView::new(&calendar_feed),
+        "token": token,
+        "path": format!("/calendar_feeds/{token}.ics"),
+    });
+    let response = HttpResponse::Created().json(response_json);
+    Ok(response)
+}
+
+async fn destroy(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+    app_state
+        .repositories
+        .calendar_feed()
+        .destroy(&workplace)
+        .await?;
+
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+struct FeedPath {
+    token: String,
+}
+
+async fn feed(
+    app_state: Data<AppState>,
+    path: Path<FeedPath>,
+) -> Result<HttpResponse, PerRequestError> {
+    let calendar_feed = find_calendar_feed(&app_state, &path.token)
+        .await?
+        .ok_or(PerRequestError::NotFound)?;
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(
+            &User::new(calendar_feed.user_id),
+            calendar_feed.workplace_id,
+        )
+        .await?;
+
+    let end = Utc::now();
+    let start = end - TimeDelta::days(FEED_PERIOD_DAYS);
+    let mut attendance_records = app_state
+        .repositories
+        .attendance_record()
+        .list(&workplace, &start.into(), &end.into())
+        .await?;
+    // a feed only carries the sessions of the user who issued it, even for a manager
+    attendance_records.retain(|record| record.user_id == calendar_feed.user_id);
+    let work_sessions = WorkSession::pair(&attendance_records);
+    let body = WorkSessionCalendar::new(&workplace, &work_sessions).execute();
+
+    let response = HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .body(body);
+    Ok(response)
+}
+
+/// Looks the feed up with every digesting secret so that feeds survive a secret rotation.
+async fn find_calendar_feed(
+    app_state: &AppState,
+    token: &str,
+) -> Result<Option<CalendarFeed>, PerRequestError> {
+    let repository = app_state.repositories.calendar_feed();
+    let keyring = app_state.secrets.api_key.keyring();
+    for digest in keyring.candidate_digests(token)? {
+        if let Some(calendar_feed) = repository.find_by_digest(&digest.value).await? {
+            return Ok(Some(calendar_feed));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,98 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::{WorkSession, Workplace};
+
+const LINE_LIMIT: usize = 75;
+
+/// Renders the complete work sessions of a workplace as an iCalendar (RFC 5545) document,
+/// with one VEVENT per clock-in/clock-out pair.
+pub(super) struct WorkSessionCalendar<'a> {
+    workplace: &'a Workplace,
+    work_sessions: &'a [WorkSession],
+}
+
+impl<'a> WorkSessionCalendar<'a> {
+    pub(super) fn new(workplace: &'a Workplace, work_sessions: &'a [WorkSession]) -> Self {
+        Self {
+            workplace,
+            work_sessions,
+        }
+    }
+
+    pub(super) fn execute(self) -> String {
+        let now = Utc::now();
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_owned(),
+            "VERSION:2.0".to_owned(),
+            "PRODID:-//azarole//work sessions//EN".to_owned(),
+            "CALSCALE:GREGORIAN".to_owned(),
+            format!("X-WR-CALNAME:{}", escape(&self.workplace.name)),
+            format!("X-WR-TIMEZONE:{}", self.workplace.timezone.name()),
+        ];
+        for work_session in self.work_sessions {
+            let (Some(clock_in), Some(clock_out)) =
+                (&work_session.clock_in, &work_session.clock_out)
+            else {
+                continue;
+            };
+            let worked_minutes = work_session
+                .worked_duration()
+                .map_or(0, |duration| duration.num_minutes());
+            let break_minutes = work_session.break_duration().num_minutes();
+
+            lines.extend([
+                "BEGIN:VEVENT".to_owned(),
+                format!("UID:attendance-record-{}@azarole", clock_in.id),
+                format!("DTSTAMP:{}", format_datetime(&now)),
+                format!("DTSTART:{}", format_datetime(&clock_in.recorded_at)),
+                format!("DTEND:{}", format_datetime(&clock_out.recorded_at)),
+                format!("SUMMARY:{}", escape(&self.workplace.name)),
+                format!(
+                    "DESCRIPTION:{}",
+                    escape(&format!(
+                        "Worked {}:{:02}, break {}:{:02}",
+                        worked_minutes / 60,
+                        worked_minutes % 60,
+                        break_minutes / 60,
+                        break_minutes % 60
+                    ))
+                ),
+                "END:VEVENT".to_owned(),
+            ]);
+        }
+        lines.push("END:VCALENDAR".to_owned());
+
+        lines
+            .iter()
+            .map(|line| fold(line))
+            .collect::<Vec<String>>()
+            .join("")
+    }
+}
+
+fn format_datetime(datetime: &DateTime<Utc>) -> String {
+    datetime.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Content lines longer than 75 octets continue on the next line after a space.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > LINE_LIMIT {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
@@ -0,0 +1,45 @@
+use crate::{
+    AppState,
+    errors::{DatabaseError, PerRequestError},
+    models::{Timestamp, UserId, Workplace},
+    repositories::RepositoryFactory,
+};
+
+/// Approved timesheets lock the attendance records of their period against changes
+/// until a manager reopens them.
+pub(in crate::handlers) struct PeriodLock<'a> {
+    app_state: &'a AppState,
+    workplace: &'a Workplace,
+}
+
+impl<'a> PeriodLock<'a> {
+    pub(in crate::handlers) fn new(app_state: &'a AppState, workplace: &'a Workplace) -> Self {
+        Self {
+            app_state,
+            workplace,
+        }
+    }
+
+    pub(in crate::handlers) async fn is_locked(
+        &self,
+        user_id: UserId,
+        datetime: &Timestamp,
+    ) -> Result<bool, DatabaseError> {
+        let repository = self.app_state.repositories.timesheet();
+        let timesheet = repository
+            .find_locking(self.workplace, user_id, datetime)
+            .await?;
+        Ok(timesheet.is_some())
+    }
+
+    pub(in crate::handlers) async fn ensure_unlocked(
+        &self,
+        user_id: UserId,
+        datetime: &Timestamp,
+    ) -> Result<(), PerRequestError> {
+        if self.is_locked(user_id, datetime).await? {
+            return Err(PerRequestError::Conflict("period-locked"));
+        }
+        Ok(())
+    }
+}
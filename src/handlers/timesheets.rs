@@ -0,0 +1,266 @@
+use actix_web::{
+    HttpResponse,
+    web::{Data, Form, Path, Query, ReqData, ServiceConfig, get, post},
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{
+    attendance_records::TargetMonth,
+    views::{TimesheetView, WorkplaceView},
+};
+use crate::{
+    AppState,
+    errors::PerRequestError,
+    models::{Timesheet, User, UserId, Workplace, WorkplaceId},
+    repositories::RepositoryFactory,
+};
+
+pub(super) fn routes(config: &mut ServiceConfig) {
+    config
+        .route("", get().to(index))
+        .route("/{year}/{month}", get().to(show))
+        .route("/{year}/{month}/submit", post().to(submit))
+        .route("/{year}/{month}/approve", post().to(approve))
+        .route("/{year}/{month}/return", post().to(send_back))
+        .route("/{year}/{month}/reopen", post().to(reopen));
+}
+
+#[derive(Deserialize)]
+struct PathInfo {
+    workplace_id: WorkplaceId,
+}
+
+#[derive(Deserialize)]
+pub(super) struct PeriodPath {
+    workplace_id: WorkplaceId,
+    year: i32,
+    month: u32,
+}
+
+/// Finds the timesheet of `user_id` for a payroll month, which is open until it is first
+/// submitted. Only managers may look at the timesheets of other users in the workplace.
+async fn find_timesheet(
+    app_state: &AppState,
+    workplace: &Workplace,
+    user_id: UserId,
+    path: &PeriodPath,
+) -> Result<Timesheet, PerRequestError> {
+    if path.year < 0 || !(1..=12).contains(&path.month) {
+        return Err(PerRequestError::BadRequest);
+    }
+    if user_id != workplace.viewer.user_id {
+        if !workplace.viewer.role.manages_attendance() {
+            return Err(PerRequestError::NotFound);
+        }
+        let memberships = app_state
+            .repositories
+            .workplace_membership()
+            .list(workplace)
+            .await?;
+        if !memberships
+            .iter()
+            .any(|membership| membership.user_id == user_id)
+        {
+            return Err(PerRequestError::NotFound);
+        }
+    }
+
+    let repository = app_state.repositories.timesheet();
+    let timesheet = repository
+        .find(workplace, user_id, path.year, path.month)
+        .await?;
+    if let Some(timesheet) = timesheet {
+        return Ok(timesheet);
+    }
+
+    let period = TargetMonth::new(workplace, Some(path.year), Some(path.month))
+        .period()
+        .ok_or(PerRequestError::BadRequest)?;
+    let timesheet = Timesheet::open(
+        workplace.id,
+        user_id,
+        path.year,
+        path.month,
+        period.datetime_range(false),
+    );
+    Ok(timesheet)
+}
+
+async fn save_timesheet(
+    app_state: &AppState,
+    workplace: &Workplace,
+    timesheet: &Timesheet,
+) -> Result<HttpResponse, PerRequestError> {
+    let repository = app_state.repositories.timesheet();
+    let timesheet = repository.save(workplace, timesheet).await?;
+
+    let response_json = json!({
+        "timesheet": TimesheetView::new(&timesheet),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+struct IndexParameters {
+    year: Option<i32>,
+}
+
+async fn index(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+    params: Query<IndexParameters>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+
+    let repository = app_state.repositories.timesheet();
+    let timesheets = repository.list(&workplace, params.year).await?;
+
+    let response_json = json!({
+        "workplace": WorkplaceView::new(&workplace),
+        "timesheets": timesheets.iter().map(TimesheetView::new).collect::<Vec<TimesheetView>>(),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+pub(super) struct ShowParameters {
+    user_id: Option<UserId>,
+}
+
+pub(super) async fn show(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PeriodPath>,
+    params: Query<ShowParameters>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+
+    let user_id = params.user_id.unwrap_or(workplace.viewer.user_id);
+    let timesheet = find_timesheet(&app_state, &workplace, user_id, &path).await?;
+
+    let response_json = json!({
+        "workplace": WorkplaceView::new(&workplace),
+        "timesheet": TimesheetView::new(&timesheet),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+/// Staff submit their own timesheet.
+async fn submit(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PeriodPath>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+
+    let mut timesheet =
+        find_timesheet(&app_state, &workplace, workplace.viewer.user_id, &path).await?;
+    timesheet
+        .submit()
+        .map_err(|error| PerRequestError::Conflict(error.reason()))?;
+
+    save_timesheet(&app_state, &workplace, &timesheet).await
+}
+
+async fn find_reviewing_workplace(
+    app_state: &AppState,
+    current_user: &User,
+    workplace_id: WorkplaceId,
+) -> Result<Workplace, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(current_user, workplace_id)
+        .await?;
+    if !workplace.viewer.role.manages_attendance() {
+        return Err(PerRequestError::Forbidden);
+    }
+    Ok(workplace)
+}
+
+#[derive(Deserialize)]
+struct ReviewForm {
+    user_id: UserId,
+    comment: Option<String>,
+}
+
+impl ReviewForm {
+    fn comment(&self) -> Option<String> {
+        self.comment
+            .as_deref()
+            .map(str::trim)
+            .filter(|comment| !comment.is_empty())
+            .map(str::to_owned)
+    }
+}
+
+/// Nobody may approve their own timesheet, not even the owner of the workplace.
+async fn approve(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PeriodPath>,
+    form: Form<ReviewForm>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = find_reviewing_workplace(&app_state, &current_user, path.workplace_id).await?;
+    if form.user_id == workplace.viewer.user_id {
+        return Err(PerRequestError::Forbidden);
+    }
+
+    let mut timesheet = find_timesheet(&app_state, &workplace, form.user_id, &path).await?;
+    timesheet
+        .approve(workplace.viewer.user_id)
+        .map_err(|error| PerRequestError::Conflict(error.reason()))?;
+
+    save_timesheet(&app_state, &workplace, &timesheet).await
+}
+
+/// Returning a timesheet requires a comment telling its user what to fix.
+async fn send_back(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PeriodPath>,
+    form: Form<ReviewForm>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = find_reviewing_workplace(&app_state, &current_user, path.workplace_id).await?;
+    let comment = form.comment().ok_or(PerRequestError::BadRequest)?;
+
+    let mut timesheet = find_timesheet(&app_state, &workplace, form.user_id, &path).await?;
+    timesheet
+        .send_back(workplace.viewer.user_id, comment)
+        .map_err(|error| PerRequestError::Conflict(error.reason()))?;
+
+    save_timesheet(&app_state, &workplace, &timesheet).await
+}
+
+async fn reopen(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PeriodPath>,
+    form: Form<ReviewForm>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = find_reviewing_workplace(&app_state, &current_user, path.workplace_id).await?;
+
+    let mut timesheet = find_timesheet(&app_state, &workplace, form.user_id, &path).await?;
+    timesheet
+        .reopen(workplace.viewer.user_id, form.comment())
+        .map_err(|error| PerRequestError::Conflict(error.reason()))?;
+
+    save_timesheet(&app_state, &workplace, &timesheet).await
+}
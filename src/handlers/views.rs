@@ -1,8 +1,11 @@
 use serde::Serialize;
 
 use crate::models::{
-    ApiKey, ApiKeyId, AttendanceRecord, AttendanceRecordId, Timestamp, User, UserId, Workplace,
-    WorkplaceId, attendance_record,
+    ApiKey, ApiKeyId, AttendanceRecord, AttendanceRecordAudit, AttendanceRecordAuditId,
+    AttendanceRecordId, AuditAction, BreakPeriod, BreakRules, CalendarFeed, CalendarFeedId,
+    Channel, ClosingDay, OvertimeRules, RestDays, Role, ScopedActions, ScopedWorkplaces, Timesheet,
+    TimesheetState, Timestamp, Timezone, User, UserId, WorkSession, WorkSessionStatus, Workplace,
+    WorkplaceId, WorkplaceMembership, attendance_record,
 };
 
 #[derive(Serialize)]
@@ -10,7 +13,19 @@ use crate::models::{
 pub(in crate::handlers) struct ApiKeyView<'a> {
     id: &'a ApiKeyId,
     name: &'a String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_id: &'a Option<String>,
     created_at: &'a Timestamp,
+    workplace_ids: &'a ScopedWorkplaces,
+    actions: &'a ScopedActions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_used_at: &'a Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_used_ip: &'a Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: &'a Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_token_expires_at: &'a Option<Timestamp>,
 }
 
 impl<'a> ApiKeyView<'a> {
@@ -18,7 +33,14 @@ impl<'a> ApiKeyView<'a> {
         Self {
             id: &api_key.id,
             name: &api_key.name,
+            public_id: &api_key.public_id,
             created_at: &api_key.created_at,
+            workplace_ids: &api_key.scope.workplace_ids,
+            actions: &api_key.scope.actions,
+            last_used_at: &api_key.last_used_at,
+            last_used_ip: &api_key.last_used_ip,
+            expires_at: &api_key.expires_at,
+            previous_token_expires_at: &api_key.previous_digest_expires_at,
         }
     }
 }
@@ -27,16 +49,55 @@ impl<'a> ApiKeyView<'a> {
 #[serde(rename_all = "camelCase")]
 pub(in crate::handlers) struct AttendanceRecordView<'a> {
     id: &'a AttendanceRecordId,
+    user_id: &'a UserId,
     event: &'a attendance_record::Event,
     recorded_at: &'a Timestamp,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted_at: &'a Option<Timestamp>,
 }
 
 impl<'a> AttendanceRecordView<'a> {
     pub(in crate::handlers) fn new(attendance_record: &'a AttendanceRecord) -> Self {
         Self {
             id: &attendance_record.id,
+            user_id: &attendance_record.user_id,
             event: &attendance_record.event,
             recorded_at: &attendance_record.recorded_at,
+            deleted_at: &attendance_record.deleted_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(in crate::handlers) struct AttendanceRecordAuditView<'a> {
+    id: &'a AttendanceRecordAuditId,
+    attendance_record_id: &'a AttendanceRecordId,
+    action: &'a AuditAction,
+    user_id: &'a UserId,
+    channel: &'a Channel,
+    api_key_id: &'a Option<ApiKeyId>,
+    previous_event: &'a Option<attendance_record::Event>,
+    previous_recorded_at: &'a Option<Timestamp>,
+    event: &'a Option<attendance_record::Event>,
+    recorded_at: &'a Option<Timestamp>,
+    created_at: &'a Timestamp,
+}
+
+impl<'a> AttendanceRecordAuditView<'a> {
+    pub(in crate::handlers) fn new(audit: &'a AttendanceRecordAudit) -> Self {
+        Self {
+            id: &audit.id,
+            attendance_record_id: &audit.attendance_record_id,
+            action: &audit.action,
+            user_id: &audit.user_id,
+            channel: &audit.channel,
+            api_key_id: &audit.api_key_id,
+            previous_event: &audit.previous_event,
+            previous_recorded_at: &audit.previous_recorded_at,
+            event: &audit.event,
+            recorded_at: &audit.recorded_at,
+            created_at: &audit.created_at,
         }
     }
 }
@@ -46,6 +107,13 @@ impl<'a> AttendanceRecordView<'a> {
 pub(in crate::handlers) struct WorkplaceView<'a> {
     id: &'a WorkplaceId,
     name: &'a String,
+    timezone: &'a Timezone,
+    break_rules: &'a BreakRules,
+    overtime_rules: OvertimeRulesView<'a>,
+    closing_day: &'a ClosingDay,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archived_at: &'a Option<Timestamp>,
+    role: &'a Role,
 }
 
 impl<'a> WorkplaceView<'a> {
@@ -53,6 +121,152 @@ impl<'a> WorkplaceView<'a> {
         Self {
             id: &workplace.id,
             name: &workplace.name,
+            timezone: &workplace.timezone,
+            break_rules: &workplace.break_rules,
+            overtime_rules: OvertimeRulesView::new(&workplace.overtime_rules),
+            closing_day: &workplace.closing_day,
+            archived_at: &workplace.archived_at,
+            role: &workplace.viewer.role,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(in crate::handlers) struct WorkplaceMembershipView<'a> {
+    user_id: &'a UserId,
+    role: &'a Role,
+}
+
+impl<'a> WorkplaceMembershipView<'a> {
+    pub(in crate::handlers) fn new(membership: &'a WorkplaceMembership) -> Self {
+        Self {
+            user_id: &membership.user_id,
+            role: &membership.role,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(in crate::handlers) struct CalendarFeedView<'a> {
+    id: &'a CalendarFeedId,
+    workplace_id: &'a WorkplaceId,
+    created_at: &'a Timestamp,
+}
+
+impl<'a> CalendarFeedView<'a> {
+    pub(in crate::handlers) fn new(calendar_feed: &'a CalendarFeed) -> Self {
+        Self {
+            id: &calendar_feed.id,
+            workplace_id: &calendar_feed.workplace_id,
+            created_at: &calendar_feed.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(in crate::handlers) struct TimesheetView<'a> {
+    user_id: &'a UserId,
+    year: &'a i32,
+    month: &'a u32,
+    state: &'a TimesheetState,
+    comment: &'a Option<String>,
+    starts_at: &'a Timestamp,
+    ends_at: &'a Timestamp,
+    submitted_at: &'a Option<Timestamp>,
+    reviewed_by: &'a Option<UserId>,
+    reviewed_at: &'a Option<Timestamp>,
+}
+
+impl<'a> TimesheetView<'a> {
+    pub(in crate::handlers) fn new(timesheet: &'a Timesheet) -> Self {
+        Self {
+            user_id: &timesheet.user_id,
+            year: &timesheet.year,
+            month: &timesheet.month,
+            state: &timesheet.state,
+            comment: &timesheet.comment,
+            starts_at: &timesheet.starts_at,
+            ends_at: &timesheet.ends_at,
+            submitted_at: &timesheet.submitted_at,
+            reviewed_by: &timesheet.reviewed_by,
+            reviewed_at: &timesheet.reviewed_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(in crate::handlers) struct OvertimeRulesView<'a> {
+    daily_threshold_minutes: &'a i64,
+    weekly_threshold_minutes: &'a i64,
+    rest_days: &'a RestDays,
+}
+
+impl<'a> OvertimeRulesView<'a> {
+    pub(in crate::handlers) fn new(overtime_rules: &'a OvertimeRules) -> Self {
+        Self {
+            daily_threshold_minutes: &overtime_rules.daily_threshold_minutes,
+            weekly_threshold_minutes: &overtime_rules.weekly_threshold_minutes,
+            rest_days: &overtime_rules.rest_days,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(in crate::handlers) struct WorkSessionView<'a> {
+    user_id: &'a UserId,
+    clock_in_id: Option<&'a AttendanceRecordId>,
+    clock_out_id: Option<&'a AttendanceRecordId>,
+    started_at: Option<&'a Timestamp>,
+    ended_at: Option<&'a Timestamp>,
+    duration_seconds: Option<i64>,
+    break_seconds: i64,
+    worked_seconds: Option<i64>,
+    breaks: Vec<BreakPeriodView<'a>>,
+    status: WorkSessionStatus,
+}
+
+impl<'a> WorkSessionView<'a> {
+    pub(in crate::handlers) fn new(work_session: &'a WorkSession) -> Self {
+        Self {
+            user_id: &work_session.user_id,
+            clock_in_id: work_session.clock_in.as_ref().map(|record| &record.id),
+            clock_out_id: work_session.clock_out.as_ref().map(|record| &record.id),
+            started_at: work_session.started_at(),
+            ended_at: work_session.ended_at(),
+            duration_seconds: work_session
+                .duration()
+                .map(|duration| duration.num_seconds()),
+            break_seconds: work_session.break_duration().num_seconds(),
+            worked_seconds: work_session
+                .worked_duration()
+                .map(|duration| duration.num_seconds()),
+            breaks: work_session
+                .breaks
+                .iter()
+                .map(BreakPeriodView::new)
+                .collect(),
+            status: work_session.status(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(in crate::handlers) struct BreakPeriodView<'a> {
+    started_at: &'a Timestamp,
+    ended_at: &'a Option<Timestamp>,
+}
+
+impl<'a> BreakPeriodView<'a> {
+    pub(in crate::handlers) fn new(break_period: &'a BreakPeriod) -> Self {
+        Self {
+            started_at: &break_period.started_at,
+            ended_at: &break_period.ended_at,
         }
     }
 }
@@ -0,0 +1,139 @@
+use actix_web::{
+    HttpResponse,
+    web::{Data, Form, Path, ReqData, ServiceConfig, delete, get, patch, post},
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{views::WorkplaceMembershipView, workplaces::find_managed_workplace};
+use crate::{
+    AppState,
+    errors::PerRequestError,
+    models::{Role, User, UserId, WorkplaceId},
+    repositories::RepositoryFactory,
+};
+
+pub(super) fn routes(config: &mut ServiceConfig) {
+    config
+        .route("", get().to(index))
+        .route("", post().to(create))
+        .route("/{user_id}", patch().to(update))
+        .route("/{user_id}", delete().to(destroy));
+}
+
+#[derive(Deserialize)]
+struct PathInfo {
+    workplace_id: WorkplaceId,
+}
+
+#[derive(Deserialize)]
+struct MembershipPath {
+    workplace_id: WorkplaceId,
+    user_id: UserId,
+}
+
+/// The owner is granted by the workplace itself and cannot be handed out as a membership.
+fn assignable(role: Role) -> Result<Role, PerRequestError> {
+    match role {
+        Role::Owner => Err(PerRequestError::BadRequest),
+        _ => Ok(role),
+    }
+}
+
+async fn index(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(&current_user, path.workplace_id)
+        .await?;
+
+    let repository = app_state.repositories.workplace_membership();
+    let memberships = repository.list(&workplace).await?;
+
+    let response_json = json!({
+        "memberships": memberships.iter().map(WorkplaceMembershipView::new).collect::<Vec<WorkplaceMembershipView>>(),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+struct CreatingMembershipForm {
+    user_id: UserId,
+    role: Role,
+}
+
+async fn create(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+    form: Form<CreatingMembershipForm>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = find_managed_workplace(&app_state, &current_user, path.workplace_id).await?;
+    let role = assignable(form.role)?;
+
+    app_state
+        .repositories
+        .user()
+        .find_optional(form.user_id)
+        .await?
+        .ok_or(PerRequestError::NotFound)?;
+
+    let repository = app_state.repositories.workplace_membership();
+    let memberships = repository.list(&workplace).await?;
+    if memberships
+        .iter()
+        .any(|membership| membership.user_id == form.user_id)
+    {
+        return Err(PerRequestError::Conflict("already-member"));
+    }
+    let membership = repository.create(&workplace, form.user_id, role).await?;
+
+    let response_json = json!({
+        "membership": WorkplaceMembershipView::new(&membership),
+    });
+    let response = HttpResponse::Created().json(response_json);
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+struct UpdatingMembershipForm {
+    role: Role,
+}
+
+async fn update(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<MembershipPath>,
+    form: Form<UpdatingMembershipForm>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = find_managed_workplace(&app_state, &current_user, path.workplace_id).await?;
+    let role = assignable(form.role)?;
+
+    let repository = app_state.repositories.workplace_membership();
+    let membership = repository.update(&workplace, path.user_id, role).await?;
+
+    let response_json = json!({
+        "membership": WorkplaceMembershipView::new(&membership),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+async fn destroy(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<MembershipPath>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = find_managed_workplace(&app_state, &current_user, path.workplace_id).await?;
+
+    let repository = app_state.repositories.workplace_membership();
+    repository.destroy(&workplace, path.user_id).await?;
+
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
@@ -1,25 +1,61 @@
 use actix_web::{
     HttpResponse,
-    web::{Data, Form, ReqData, ServiceConfig, get, post},
+    web::{Data, Form, Path, Query, ReqData, ServiceConfig, delete, get, patch, post},
 };
+use chrono::Utc;
 use serde::Deserialize;
 use serde_json::json;
 
 use super::views::WorkplaceView;
-use crate::{AppState, errors::PerRequestError, models::User, repositories::RepositoryFactory};
+use crate::{
+    AppState,
+    errors::PerRequestError,
+    models::{BreakRules, ClosingDay, RestDays, Timezone, User, Workplace, WorkplaceId},
+    repositories::RepositoryFactory,
+};
 
 pub(super) fn routes(config: &mut ServiceConfig) {
     config
         .route("", get().to(index))
-        .route("", post().to(create));
+        .route("", post().to(create))
+        .route("/{workplace_id}", patch().to(update))
+        .route("/{workplace_id}", delete().to(destroy))
+        .route("/{workplace_id}/archive", post().to(archive))
+        .route("/{workplace_id}/unarchive", post().to(unarchive));
+}
+
+/// Finds a workplace the current user owns; managers and members are refused.
+pub(super) async fn find_managed_workplace(
+    app_state: &AppState,
+    current_user: &User,
+    workplace_id: WorkplaceId,
+) -> Result<Workplace, PerRequestError> {
+    let workplace = app_state
+        .repositories
+        .workplace()
+        .find(current_user, workplace_id)
+        .await?;
+    if !workplace.viewer.role.manages_workplace() {
+        return Err(PerRequestError::Forbidden);
+    }
+    Ok(workplace)
+}
+
+#[derive(Deserialize)]
+struct IndexParameters {
+    #[serde(default)]
+    include_archived: bool,
 }
 
 async fn index(
     app_state: Data<AppState>,
     current_user: ReqData<User>,
+    params: Query<IndexParameters>,
 ) -> Result<HttpResponse, PerRequestError> {
     let repository = app_state.repositories.workplace();
-    let workplaces = repository.list(&current_user).await?;
+    let workplaces = repository
+        .list(&current_user, params.include_archived)
+        .await?;
 
     let response_json = json!({
         "workplaces": workplaces.iter().map(WorkplaceView::new).collect::<Vec<WorkplaceView>>(),
@@ -31,6 +67,8 @@ async fn index(
 #[derive(Deserialize)]
 struct CreatingWorkplaceForm {
     name: String,
+    timezone: Option<Timezone>,
+    break_rules: Option<BreakRules>,
 }
 
 async fn create(
@@ -38,8 +76,12 @@ async fn create(
     current_user: ReqData<User>,
     form: Form<CreatingWorkplaceForm>,
 ) -> Result<HttpResponse, PerRequestError> {
+    let timezone = form.timezone.unwrap_or_default();
+    let break_rules = form.break_rules.clone().unwrap_or_default();
     let repository = app_state.repositories.workplace();
-    let workpalce = repository.create(&current_user, &form.name).await?;
+    let workpalce = repository
+        .create(&current_user, &form.name, &timezone, &break_rules)
+        .await?;
 
     let response_json = json!({
         "workplace": WorkplaceView::new(&workpalce),
@@ -47,3 +89,139 @@ async fn create(
     let response = HttpResponse::Created().json(response_json);
     Ok(response)
 }
+
+#[derive(Deserialize)]
+struct PathInfo {
+    workplace_id: WorkplaceId,
+}
+
+#[derive(Deserialize)]
+struct UpdatingWorkplaceForm {
+    name: Option<String>,
+    timezone: Option<Timezone>,
+    break_rules: Option<BreakRules>,
+    daily_overtime_threshold_minutes: Option<u32>,
+    weekly_overtime_threshold_minutes: Option<u32>,
+    rest_days: Option<RestDays>,
+    closing_day: Option<ClosingDay>,
+}
+
+async fn update(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+    form: Form<UpdatingWorkplaceForm>,
+) -> Result<HttpResponse, PerRequestError> {
+    let repository = app_state.repositories.workplace();
+    let mut workplace =
+        find_managed_workplace(&app_state, &current_user, path.workplace_id).await?;
+
+    if let Some(name) = &form.name {
+        workplace.name = name.clone();
+    }
+    if let Some(timezone) = form.timezone {
+        workplace.timezone = timezone;
+    }
+    if let Some(break_rules) = &form.break_rules {
+        workplace.break_rules = break_rules.clone();
+    }
+    if let Some(minutes) = form.daily_overtime_threshold_minutes {
+        workplace.overtime_rules.daily_threshold_minutes = minutes.into();
+    }
+    if let Some(minutes) = form.weekly_overtime_threshold_minutes {
+        workplace.overtime_rules.weekly_threshold_minutes = minutes.into();
+    }
+    if let Some(rest_days) = &form.rest_days {
+        workplace.overtime_rules.rest_days = rest_days.clone();
+    }
+    if let Some(closing_day) = form.closing_day {
+        workplace.closing_day = closing_day;
+    }
+    let workplace = repository.update(&workplace).await?;
+
+    let response_json = json!({
+        "workplace": WorkplaceView::new(&workplace),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+/// Archived workplaces are hidden from the default listing but can still be found for reports.
+async fn archive(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+) -> Result<HttpResponse, PerRequestError> {
+    let repository = app_state.repositories.workplace();
+    let mut workplace =
+        find_managed_workplace(&app_state, &current_user, path.workplace_id).await?;
+
+    if workplace.archived_at.is_none() {
+        workplace.archived_at = Some(Utc::now().into());
+        workplace = repository.update(&workplace).await?;
+    }
+
+    let response_json = json!({
+        "workplace": WorkplaceView::new(&workplace),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+async fn unarchive(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+) -> Result<HttpResponse, PerRequestError> {
+    let repository = app_state.repositories.workplace();
+    let mut workplace =
+        find_managed_workplace(&app_state, &current_user, path.workplace_id).await?;
+
+    if workplace.archived_at.is_some() {
+        workplace.archived_at = None;
+        workplace = repository.update(&workplace).await?;
+    }
+
+    let response_json = json!({
+        "workplace": WorkplaceView::new(&workplace),
+    });
+    let response = HttpResponse::Ok().json(response_json);
+    Ok(response)
+}
+
+/// What to do with the attendance records, including trashed ones, of a workplace being deleted.
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum DeletionPolicy {
+    #[default]
+    Refuse,
+    Cascade,
+}
+
+#[derive(Deserialize)]
+struct DeletionParameters {
+    #[serde(default)]
+    attendance_records: DeletionPolicy,
+}
+
+async fn destroy(
+    app_state: Data<AppState>,
+    current_user: ReqData<User>,
+    path: Path<PathInfo>,
+    params: Query<DeletionParameters>,
+) -> Result<HttpResponse, PerRequestError> {
+    let workplace = find_managed_workplace(&app_state, &current_user, path.workplace_id).await?;
+
+    let including_attendance_records = matches!(params.attendance_records, DeletionPolicy::Cascade);
+    let deleted = app_state
+        .repositories
+        .workplace()
+        .delete_cascading(&workplace, including_attendance_records)
+        .await?;
+    if !deleted {
+        return Err(PerRequestError::Conflict("has-attendance-records"));
+    }
+
+    let response = HttpResponse::Ok().finish();
+    Ok(response)
+}
@@ -1,3 +1,4 @@
+pub mod access_log;
 pub mod args;
 pub mod config;
 pub mod context;
@@ -5,7 +6,9 @@ mod errors;
 pub mod handlers;
 mod middlewares;
 pub mod models;
+pub mod rate_limiter;
 pub mod repositories;
 pub mod secrets;
+pub mod tasks;
 
 pub use context::AppState;
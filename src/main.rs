@@ -2,7 +2,6 @@ use actix_cors::Cors;
 use actix_session::{SessionMiddleware, storage::CookieSessionStore};
 use actix_web::cookie::Key;
 use actix_web::http::header;
-use actix_web::middleware::Logger;
 use actix_web::web::Data;
 use actix_web::{App, HttpServer};
 use env_logger::Env;
@@ -13,7 +12,7 @@ use azarole::config::ApplicationConfig;
 fn build_cors(config: &ApplicationConfig) -> Cors {
     Cors::default()
         .allowed_origin(&config.frontend.base_url)
-        .allowed_methods(vec!["POST", "GET", "DELETE", "OPTIONS"])
+        .allowed_methods(vec!["POST", "GET", "PATCH", "DELETE", "OPTIONS"])
         .allowed_headers(vec![header::CONTENT_TYPE])
         .supports_credentials()
 }
@@ -34,11 +33,14 @@ async fn main() -> anyhow::Result<()> {
     let app_state = AppState::new(&config)?;
     let server_config = config.server.clone();
 
+    actix_rt::spawn(azarole::tasks::purge_trash_periodically(app_state.clone()));
+    actix_rt::spawn(azarole::tasks::purge_idempotency_keys_periodically(
+        app_state.clone(),
+    ));
+
     let server = HttpServer::new(move || {
         App::new()
-            .wrap(Logger::new(
-                "%a %t \"%r\" %s %b \"%{Referer}i\" \"%{User-Agent}i\" %T",
-            ))
+            .wrap(azarole::access_log::logger())
             .wrap(build_cors(&config))
             .wrap(build_session_middleware(&app_state))
             .app_data(Data::new(app_state.clone()))
@@ -1,2 +1,3 @@
+pub mod rate_limit;
 pub mod require_api_key;
 pub mod require_signin;
@@ -0,0 +1,119 @@
+use std::rc::Rc;
+use std::time::Instant;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::InternalError;
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use actix_web::web::Data;
+use actix_web::{Error, HttpResponse};
+use futures_util::future::{LocalBoxFuture, Ready, ok};
+
+use crate::AppState;
+use crate::rate_limiter::{BucketKey, BucketStatus, RateLimitDecision};
+
+const X_RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+const X_RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+const X_RATELIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+/// Limits requests per client IP. It runs ahead of `RequireApiKey`, so that floods with bad
+/// tokens are turned away before looking up their keys, which limits requests per key in turn.
+pub struct RateLimit;
+
+impl RateLimit {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    S::Error: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RateLimitMiddleware {
+            service: Rc::new(service),
+        })
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let app_state: &Data<AppState> = req.app_data().unwrap();
+            let config = &app_state.config.rate_limit;
+
+            let Some(peer_addr) = req.peer_addr() else {
+                return service.call(req).await;
+            };
+            let buckets = [(BucketKey::Ip(peer_addr.ip()), &config.per_ip)];
+            match app_state.rate_limiter.check(&buckets, Instant::now()) {
+                RateLimitDecision::Allowed(status) => {
+                    let mut response = service.call(req).await?;
+                    if let Some(status) = status {
+                        insert_headers(response.headers_mut(), &status);
+                    }
+                    Ok(response)
+                }
+                RateLimitDecision::Limited(status) => Err(too_many_requests(&status)),
+            }
+        })
+    }
+}
+
+/// The response to a request refused by the limiter.
+pub(super) fn too_many_requests(status: &BucketStatus) -> Error {
+    let mut response = HttpResponse::TooManyRequests().finish();
+    insert_headers(response.headers_mut(), status);
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, seconds_until_reset(status));
+    InternalError::from_response("too many requests", response).into()
+}
+
+/// Tells the status of the tightest bucket, keeping the headers of a tighter one that was
+/// checked further in.
+pub(super) fn insert_headers(headers: &mut HeaderMap, status: &BucketStatus) {
+    let remaining = headers
+        .get(X_RATELIMIT_REMAINING)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u32>().ok());
+    if remaining.is_some_and(|remaining| remaining <= status.remaining) {
+        return;
+    }
+    headers.insert(X_RATELIMIT_LIMIT, HeaderValue::from(status.limit));
+    headers.insert(X_RATELIMIT_REMAINING, HeaderValue::from(status.remaining));
+    headers.insert(X_RATELIMIT_RESET, seconds_until_reset(status));
+}
+
+fn seconds_until_reset(status: &BucketStatus) -> HeaderValue {
+    // rounded up so that clients never retry before the window has reset
+    let seconds = status.resets_in.as_millis().div_ceil(1000) as u64;
+    HeaderValue::from(seconds)
+}
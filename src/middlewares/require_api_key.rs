@@ -1,4 +1,5 @@
 use std::rc::Rc;
+use std::time::Instant;
 
 use actix_web::body::MessageBody;
 use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
@@ -8,9 +9,14 @@ use actix_web_httpauth::extractors::bearer::BearerAuth;
 use futures_util::future::{LocalBoxFuture, Ready, ok};
 
 mod api_key_authenticator;
+mod requested_operation;
 
+use super::rate_limit::{insert_headers, too_many_requests};
 use crate::AppState;
+use crate::models::{Actor, User};
+use crate::rate_limiter::{BucketKey, RateLimitDecision};
 use api_key_authenticator::ApiKeyAuthenticator;
+use requested_operation::{RequestedOperation, is_permitted};
 
 pub struct RequireApiKey;
 
@@ -65,19 +71,41 @@ where
             let bearer_auth = BearerAuth::extract(req.request()).await?;
             let token = bearer_auth.token();
 
-            let authenticator = ApiKeyAuthenticator::new(app_state, token);
-            let result = authenticator.authenticate().await;
-            match result {
-                Ok(Some(user)) => {
-                    req.extensions_mut().insert(user);
-                    let response = service.call(req).await?;
-                    Ok(response)
-                }
-                Ok(None) => Err(actix_web::error::ErrorUnauthorized("unauthorized")),
-                Err(_err) => Err(actix_web::error::ErrorInternalServerError(
-                    "internal server error",
-                )),
+            let client_ip = req.peer_addr().map(|addr| addr.ip().to_string());
+            let authenticator = ApiKeyAuthenticator::new(app_state, token, client_ip.as_deref());
+            let mut api_key = match authenticator.authenticate().await {
+                Ok(Some(api_key)) => api_key,
+                Ok(None) => return Err(actix_web::error::ErrorUnauthorized("unauthorized")),
+                Err(_err) => return Err(internal_server_error()),
+            };
+
+            // limited before recording the use, so that a refused request costs no write
+            let config = &app_state.config.rate_limit;
+            let buckets = [(BucketKey::ApiKey(api_key.id), &config.per_key)];
+            let status = match app_state.rate_limiter.check(&buckets, Instant::now()) {
+                RateLimitDecision::Allowed(status) => status,
+                RateLimitDecision::Limited(status) => return Err(too_many_requests(&status)),
+            };
+            if authenticator.record_usage(&mut api_key).await.is_err() {
+                return Err(internal_server_error());
+            }
+
+            let operation = RequestedOperation::parse(req.method(), req.match_info().unprocessed());
+            if !is_permitted(&api_key.scope, operation.as_ref()) {
+                return Err(actix_web::error::ErrorForbidden("forbidden"));
             }
+
+            req.extensions_mut().insert(User::new(api_key.user_id));
+            req.extensions_mut().insert(Actor::with_api_key(&api_key));
+            let mut response = service.call(req).await?;
+            if let Some(status) = status {
+                insert_headers(response.headers_mut(), &status);
+            }
+            Ok(response)
         })
     }
 }
+
+fn internal_server_error() -> Error {
+    actix_web::error::ErrorInternalServerError("internal server error")
+}
@@ -1,30 +1,63 @@
 use anyhow::Result;
+use chrono::Utc;
 
 use crate::AppState;
-use crate::models::{TokenDigester, User};
+use crate::models::{ApiKey, Timestamp};
 use crate::repositories::RepositoryFactory;
 
 pub(super) struct ApiKeyAuthenticator<'a> {
     app_state: &'a AppState,
     token: &'a str,
+    client_ip: Option<&'a str>,
 }
 
 impl<'a> ApiKeyAuthenticator<'a> {
-    pub(super) fn new(app_state: &'a AppState, token: &'a str) -> Self {
-        Self { app_state, token }
+    pub(super) fn new(app_state: &'a AppState, token: &'a str, client_ip: Option<&'a str>) -> Self {
+        Self {
+            app_state,
+            token,
+            client_ip,
+        }
     }
 
-    pub(super) async fn authenticate(self) -> Result<Option<User>> {
-        let digest = self.digest_token()?;
+    /// Finds the unexpired key for the token.
+    pub(super) async fn authenticate(&self) -> Result<Option<ApiKey>> {
+        let keyring = self.app_state.secrets.api_key.keyring();
+        let digests = keyring.candidate_digests(self.token)?;
         let repository = self.app_state.repositories.api_key();
-        match repository.find_by_digest(&digest).await? {
-            Some(api_key) => Ok(Some(User::new(api_key.user_id))),
-            _ => Ok(None),
+        let found = match ApiKey::public_id_of(self.token) {
+            Some(public_id) => repository.find_by_public_id(public_id).await?,
+            None => repository.find_by_digest(&digests).await?,
+        };
+        let now = Timestamp::from(Utc::now());
+        let Some(mut api_key) = found.filter(|api_key| api_key.matches(&digests, &now)) else {
+            return Ok(None);
+        };
+        if api_key.is_expired_at(&now) {
+            return Ok(None);
+        }
+
+        // keys found through a retired secret move over to the current one
+        if api_key.matches_current(&digests)
+            && api_key.digest_key_id.as_deref() != Some(keyring.current_key_id())
+        {
+            let digest = keyring.digest_token(self.token)?;
+            repository.update_digest(&api_key.id, &digest).await?;
+            api_key.digest = digest.value;
+            api_key.digest_key_id = Some(digest.key_id);
         }
+        Ok(Some(api_key))
     }
 
-    fn digest_token(&self) -> Result<String> {
-        let digester = TokenDigester::new(&self.app_state.secrets.api_key.digesting_secret_key);
-        digester.digest_token(self.token)
+    /// Records this use of the key, once the request is let through the rate limit.
+    pub(super) async fn record_usage(&self, api_key: &mut ApiKey) -> Result<()> {
+        let now = Timestamp::from(Utc::now());
+        let repository = self.app_state.repositories.api_key();
+        repository
+            .record_usage(&api_key.id, &now, self.client_ip)
+            .await?;
+        api_key.last_used_at = Some(now);
+        api_key.last_used_ip = self.client_ip.map(str::to_string);
+        Ok(())
     }
 }
@@ -0,0 +1,46 @@
+use actix_web::http::Method;
+
+use crate::models::{ApiKeyAction, ApiKeyScope, WorkplaceId};
+
+/// The workplace and action an `/api` request operates on, derived from its
+/// method and its path below the scope. Keep in sync with `handlers::api::routes`.
+pub(super) struct RequestedOperation {
+    workplace_id: WorkplaceId,
+    action: ApiKeyAction,
+}
+
+impl RequestedOperation {
+    pub(super) fn parse(method: &Method, path: &str) -> Option<Self> {
+        let segments = path.trim_matches('/').split('/').collect::<Vec<&str>>();
+        let (workplace_id, action) = match (method, segments.as_slice()) {
+            (&Method::POST, ["workplaces", workplace_id, resource]) => {
+                let action = match *resource {
+                    "clock_ins" => ApiKeyAction::ClockIn,
+                    "clock_outs" => ApiKeyAction::ClockOut,
+                    "break_starts" => ApiKeyAction::BreakStart,
+                    "break_ends" => ApiKeyAction::BreakEnd,
+                    _ => return None,
+                };
+                (workplace_id, action)
+            }
+            (&Method::GET, ["workplaces", workplace_id, _, ..]) => {
+                (workplace_id, ApiKeyAction::Read)
+            }
+            _ => return None,
+        };
+        let workplace_id = WorkplaceId::from(workplace_id.parse::<u32>().ok()?);
+        Some(Self {
+            workplace_id,
+            action,
+        })
+    }
+}
+
+/// Whether a key with `scope` may serve the request. Restricted keys are
+/// refused anything that cannot be resolved to a workplace and an action.
+pub(super) fn is_permitted(scope: &ApiKeyScope, operation: Option<&RequestedOperation>) -> bool {
+    match operation {
+        Some(operation) => scope.permits(operation.workplace_id, operation.action),
+        None => !scope.is_restricted(),
+    }
+}
@@ -9,7 +9,7 @@ use futures_util::future::{LocalBoxFuture, Ready, ok};
 
 use crate::AppState;
 use crate::errors::DatabaseError;
-use crate::models::{User, UserId};
+use crate::models::{Actor, User, UserId};
 use crate::repositories::RepositoryFactory;
 
 pub struct RequireSignin;
@@ -82,6 +82,7 @@ where
 
             match result {
                 Ok(Some(user)) => {
+                    req.extensions_mut().insert(Actor::with_session(&user));
                     req.extensions_mut().insert(user);
                     service.call(req).await
                 }
@@ -1,18 +1,41 @@
+pub mod actor;
 pub mod api_key;
+pub mod api_key_scope;
 pub mod attendance_record;
+pub mod attendance_record_audit;
+pub mod break_rule;
+pub mod calendar_feed;
+pub mod idempotency_key;
+pub mod overtime_rule;
+pub mod timesheet;
 pub mod user;
+pub mod work_session;
 pub mod workplace;
+pub mod workplace_membership;
 
-pub use api_key::{ApiKey, ApiKeyId, TokenDigester, TokenGenerator};
+pub use actor::{Actor, Channel};
+pub use api_key::{
+    ApiKey, ApiKeyId, TOKEN_PREFIX, TokenDigest, TokenDigester, TokenGenerator, TokenKeyring,
+};
+pub use api_key_scope::{ApiKeyAction, ApiKeyScope, ScopedActions, ScopedWorkplaces};
 pub use attendance_record::{AttendanceRecord, AttendanceRecordId};
+pub use attendance_record_audit::{AttendanceRecordAudit, AttendanceRecordAuditId, AuditAction};
+pub use break_rule::{BreakRule, BreakRules};
+pub use calendar_feed::{CalendarFeed, CalendarFeedId};
 use chrono::{DateTime, Utc};
+pub use idempotency_key::IdempotencyKey;
+pub use overtime_rule::{OvertimeRules, RestDays};
 use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+pub use timesheet::{Timesheet, TimesheetState};
 pub use user::{User, UserId};
-pub use workplace::{Workplace, WorkplaceId};
+pub use work_session::{BreakPeriod, WorkSession, WorkSessionStatus};
+pub use workplace::{ClosingDay, Timezone, Workplace, WorkplaceId};
+pub use workplace_membership::{Role, Viewer, WorkplaceMembership};
 
 type IdType = u32;
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, sqlx::Type)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize, sqlx::Type)]
 #[sqlx(transparent)]
 #[repr(transparent)]
 pub struct Timestamp(DateTime<Utc>);
@@ -22,3 +45,11 @@ impl From<DateTime<Utc>> for Timestamp {
         Self(value)
     }
 }
+
+impl Deref for Timestamp {
+    type Target = DateTime<Utc>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ApiKey, ApiKeyId, User, UserId};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum Channel {
+    Session,
+    ApiKey,
+}
+
+/// The signed-in user together with the way the request was authenticated.
+#[derive(Clone)]
+pub struct Actor {
+    pub user_id: UserId,
+    pub channel: Channel,
+    pub api_key_id: Option<ApiKeyId>,
+}
+
+impl Actor {
+    pub fn with_session(user: &User) -> Self {
+        Self {
+            user_id: user.id,
+            channel: Channel::Session,
+            api_key_id: None,
+        }
+    }
+
+    pub fn with_api_key(api_key: &ApiKey) -> Self {
+        Self {
+            user_id: api_key.user_id,
+            channel: Channel::ApiKey,
+            api_key_id: Some(api_key.id),
+        }
+    }
+}
@@ -3,22 +3,76 @@ use rand::{RngExt, rngs::StdRng};
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use sqlx::prelude::FromRow;
+use subtle::ConstantTimeEq;
 
-use super::{IdType, Timestamp, UserId};
+use super::{ApiKeyScope, IdType, Timestamp, UserId};
 
-#[derive(Clone, Copy, Deserialize, Serialize, sqlx::Type)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Hash, Serialize, sqlx::Type)]
 #[sqlx(transparent)]
 #[repr(transparent)]
 pub struct ApiKeyId(IdType);
 
+/// Marks issued tokens, which read `azr_<public id>_<secret>`.
+pub const TOKEN_PREFIX: &str = "azr_";
+
 #[derive(Clone, Deserialize, FromRow, Serialize)]
 #[allow(dead_code)]
 pub struct ApiKey {
     pub id: ApiKeyId,
     pub user_id: UserId,
     pub name: String,
+    /// Non-secret identifier embedded in the token; `None` for keys issued without a prefix.
+    pub public_id: Option<String>,
     pub digest: String,
+    /// Identifier of the secret `digest` was made with; `None` for keys issued before secrets had identifiers.
+    pub digest_key_id: Option<String>,
+    /// Digest of the token replaced by the last rotation, accepted until `previous_digest_expires_at`.
+    pub previous_digest: Option<String>,
+    pub previous_digest_expires_at: Option<Timestamp>,
     pub created_at: Timestamp,
+    #[sqlx(flatten)]
+    pub scope: ApiKeyScope,
+    pub last_used_at: Option<Timestamp>,
+    pub last_used_ip: Option<String>,
+    pub expires_at: Option<Timestamp>,
+}
+
+impl ApiKey {
+    /// Extracts the public id from a prefixed token.
+    pub fn public_id_of(token: &str) -> Option<&str> {
+        let (public_id, _secret) = token.strip_prefix(TOKEN_PREFIX)?.split_once('_')?;
+        Some(public_id).filter(|public_id| !public_id.is_empty())
+    }
+
+    /// Whether one of `digests` is the current token of this key.
+    pub fn matches_current(&self, digests: &[TokenDigest]) -> bool {
+        digests
+            .iter()
+            .any(|digest| digest_eq(&digest.value, &self.digest))
+    }
+
+    /// Whether one of `digests` belongs to this key, counting the token replaced by
+    /// the last rotation until its grace period ends.
+    pub fn matches(&self, digests: &[TokenDigest], now: &Timestamp) -> bool {
+        if self.matches_current(digests) {
+            return true;
+        }
+        match (&self.previous_digest, &self.previous_digest_expires_at) {
+            (Some(previous_digest), Some(expires_at)) => {
+                now < expires_at
+                    && digests
+                        .iter()
+                        .any(|digest| digest_eq(&digest.value, previous_digest))
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_expired_at(&self, now: &Timestamp) -> bool {
+        self.expires_at
+            .as_ref()
+            .is_some_and(|expires_at| expires_at <= now)
+    }
 }
 
 #[derive(Default)]
@@ -32,6 +86,15 @@ impl TokenGenerator {
 
         bytes.into()
     }
+
+    /// A short hex identifier that may safely appear in listings and logs.
+    pub fn generate_public_id(&self) -> String {
+        let mut rng: StdRng = rand::make_rng();
+        let mut bytes = [0u8; 6];
+        rng.fill(&mut bytes[..]);
+
+        hex::encode(bytes)
+    }
 }
 
 pub struct TokenDigester {
@@ -57,3 +120,64 @@ impl TokenDigester {
         Ok(digest)
     }
 }
+
+/// A token digest together with the identifier of the secret that produced it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenDigest {
+    pub key_id: String,
+    pub value: String,
+}
+
+/// Digesting secrets by identifier. The current secret digests newly issued tokens,
+/// previous ones are only tried when looking up tokens issued before a rotation.
+pub struct TokenKeyring {
+    current_key_id: String,
+    digesters: Vec<(String, TokenDigester)>,
+}
+
+impl TokenKeyring {
+    pub fn new(key_id: &str, secret_key: &[u8]) -> Self {
+        Self {
+            current_key_id: key_id.to_string(),
+            digesters: vec![(key_id.to_string(), TokenDigester::new(secret_key))],
+        }
+    }
+
+    pub fn with_previous(mut self, key_id: &str, secret_key: &[u8]) -> Self {
+        self.digesters
+            .push((key_id.to_string(), TokenDigester::new(secret_key)));
+        self
+    }
+
+    pub fn current_key_id(&self) -> &str {
+        &self.current_key_id
+    }
+
+    /// Digests `token` with the current secret.
+    pub fn digest_token(&self, token: &str) -> anyhow::Result<TokenDigest> {
+        let (key_id, digester) = &self.digesters[0];
+        Ok(TokenDigest {
+            key_id: key_id.clone(),
+            value: digester.digest_token(token)?,
+        })
+    }
+
+    /// Digests `token` with every secret, the current one first.
+    pub fn candidate_digests(&self, token: &str) -> anyhow::Result<Vec<TokenDigest>> {
+        self.digesters
+            .iter()
+            .map(|(key_id, digester)| {
+                Ok(TokenDigest {
+                    key_id: key_id.clone(),
+                    value: digester.digest_token(token)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Compares digests in constant time, so that timing tells nothing about how much of a
+/// guessed token's digest matched.
+fn digest_eq(a: &str, b: &str) -> bool {
+    a.as_bytes().ct_eq(b.as_bytes()).into()
+}
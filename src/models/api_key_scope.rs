@@ -0,0 +1,165 @@
+use std::{fmt, num::ParseIntError, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use thiserror::Error;
+
+use super::{IdType, WorkplaceId};
+
+/// An operation an API key may be allowed to perform.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyAction {
+    ClockIn,
+    ClockOut,
+    BreakStart,
+    BreakEnd,
+    Read,
+}
+
+impl FromStr for ApiKeyAction {
+    type Err = ScopeParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "clock_in" => Ok(Self::ClockIn),
+            "clock_out" => Ok(Self::ClockOut),
+            "break_start" => Ok(Self::BreakStart),
+            "break_end" => Ok(Self::BreakEnd),
+            "read" => Ok(Self::Read),
+            _ => Err(ScopeParseError::UnknownAction),
+        }
+    }
+}
+
+impl fmt::Display for ApiKeyAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            Self::ClockIn => "clock_in",
+            Self::ClockOut => "clock_out",
+            Self::BreakStart => "break_start",
+            Self::BreakEnd => "break_end",
+            Self::Read => "read",
+        };
+        f.write_str(value)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ScopeParseError {
+    #[error("scope contains an invalid workplace id")]
+    InvalidWorkplaceId(#[from] ParseIntError),
+    #[error("scope contains an unknown action")]
+    UnknownAction,
+}
+
+/// Workplaces an API key is restricted to, stored and exchanged as e.g. `1,3`.
+/// An empty list leaves the key usable on every workplace of its user.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ScopedWorkplaces(Vec<WorkplaceId>);
+
+impl ScopedWorkplaces {
+    pub fn ids(&self) -> &[WorkplaceId] {
+        &self.0
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        !self.0.is_empty()
+    }
+
+    pub fn permits(&self, workplace_id: WorkplaceId) -> bool {
+        !self.is_restricted() || self.0.contains(&workplace_id)
+    }
+}
+
+impl TryFrom<String> for ScopedWorkplaces {
+    type Error = ScopeParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut ids = value
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| Ok(WorkplaceId::from(id.parse::<IdType>()?)))
+            .collect::<Result<Vec<WorkplaceId>, Self::Error>>()?;
+        ids.sort();
+        ids.dedup();
+        Ok(Self(ids))
+    }
+}
+
+impl From<ScopedWorkplaces> for String {
+    fn from(value: ScopedWorkplaces) -> Self {
+        value
+            .0
+            .iter()
+            .map(WorkplaceId::to_string)
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+/// Actions an API key is restricted to, stored and exchanged as e.g. `clock_in,read`.
+/// An empty list leaves every action open to the key.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ScopedActions(Vec<ApiKeyAction>);
+
+impl ScopedActions {
+    pub fn is_restricted(&self) -> bool {
+        !self.0.is_empty()
+    }
+
+    pub fn permits(&self, action: ApiKeyAction) -> bool {
+        !self.is_restricted() || self.0.contains(&action)
+    }
+}
+
+impl TryFrom<String> for ScopedActions {
+    type Error = ScopeParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut actions = value
+            .split(',')
+            .map(str::trim)
+            .filter(|action| !action.is_empty())
+            .map(str::parse::<ApiKeyAction>)
+            .collect::<Result<Vec<ApiKeyAction>, Self::Error>>()?;
+        actions.sort();
+        actions.dedup();
+        Ok(Self(actions))
+    }
+}
+
+impl From<ScopedActions> for String {
+    fn from(value: ScopedActions) -> Self {
+        value
+            .0
+            .iter()
+            .map(ApiKeyAction::to_string)
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+/// Restrictions on what an API key may do. Keys without any restriction act
+/// as their user on every workplace.
+#[derive(Clone, Debug, Default, Deserialize, FromRow, PartialEq, Eq, Serialize)]
+pub struct ApiKeyScope {
+    #[sqlx(try_from = "String")]
+    pub workplace_ids: ScopedWorkplaces,
+    #[sqlx(try_from = "String")]
+    pub actions: ScopedActions,
+}
+
+impl ApiKeyScope {
+    pub fn is_restricted(&self) -> bool {
+        self.workplace_ids.is_restricted() || self.actions.is_restricted()
+    }
+
+    /// Whether the key may perform `action` on `workplace_id`.
+    pub fn permits(&self, workplace_id: WorkplaceId, action: ApiKeyAction) -> bool {
+        self.workplace_ids.permits(workplace_id) && self.actions.permits(action)
+    }
+}
@@ -1,25 +1,146 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use thiserror::Error;
 
-use super::{IdType, Timestamp, WorkplaceId};
+use super::{IdType, Timestamp, UserId, WorkplaceId};
 
-#[derive(Clone, Copy, Deserialize, Serialize, sqlx::Type)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize, sqlx::Type)]
 #[sqlx(transparent)]
 #[repr(transparent)]
 pub struct AttendanceRecordId(IdType);
 
+impl fmt::Display for AttendanceRecordId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, sqlx::Type)]
 #[sqlx(rename_all = "kebab-case")]
 #[serde(rename_all = "kebab-case")]
 pub enum Event {
     ClockIn,
     ClockOut,
+    BreakStart,
+    BreakEnd,
 }
 
 #[derive(Clone, Deserialize, FromRow, Serialize)]
 pub struct AttendanceRecord {
     pub id: AttendanceRecordId,
     pub workplace_id: WorkplaceId,
+    pub user_id: UserId,
     pub event: Event,
     pub recorded_at: Timestamp,
+    #[sqlx(default)]
+    pub deleted_at: Option<Timestamp>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttendanceState {
+    ClockedOut,
+    ClockedIn,
+    OnBreak,
+}
+
+#[derive(Debug, Error)]
+pub enum TransitionError {
+    #[error("already clocked in")]
+    AlreadyClockedIn,
+
+    #[error("already clocked out")]
+    AlreadyClockedOut,
+
+    #[error("not clocked in")]
+    NotClockedIn,
+
+    #[error("already on break")]
+    AlreadyOnBreak,
+
+    #[error("not on break")]
+    NotOnBreak,
+}
+
+/// What a new record of a user at some moment would be placed among: whether an approved
+/// timesheet locks the moment, and the records right before and after it.
+pub struct Placement {
+    pub locked: bool,
+    pub previous: Option<AttendanceRecord>,
+    pub next: Option<AttendanceRecord>,
+}
+
+pub enum PlacementError {
+    Locked,
+    /// The previous record, which may be the same event made twice, does not accept the event.
+    NotAccepted(TransitionError, Option<AttendanceRecord>),
+    /// The next record would no longer be accepted after the event.
+    BreaksNext(TransitionError),
+}
+
+impl Placement {
+    pub fn check(self, event: &Event) -> Result<(), PlacementError> {
+        if self.locked {
+            return Err(PlacementError::Locked);
+        }
+        let state = match AttendanceState::after(self.previous.as_ref()).transition(event) {
+            Ok(state) => state,
+            Err(error) => return Err(PlacementError::NotAccepted(error, self.previous)),
+        };
+        if let Some(next) = &self.next {
+            state
+                .transition(&next.event)
+                .map_err(PlacementError::BreaksNext)?;
+        }
+        Ok(())
+    }
+}
+
+impl TransitionError {
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::AlreadyClockedIn => "already-clocked-in",
+            Self::AlreadyClockedOut => "already-clocked-out",
+            Self::NotClockedIn => "not-clocked-in",
+            Self::AlreadyOnBreak => "already-on-break",
+            Self::NotOnBreak => "not-on-break",
+        }
+    }
+}
+
+impl AttendanceState {
+    /// The state right after `record`, where no record at all means clocked out.
+    pub fn after(record: Option<&AttendanceRecord>) -> Self {
+        record
+            .map(|record| Self::after_event(&record.event))
+            .unwrap_or(Self::ClockedOut)
+    }
+
+    pub fn after_event(event: &Event) -> Self {
+        match event {
+            Event::ClockIn | Event::BreakEnd => Self::ClockedIn,
+            Event::ClockOut => Self::ClockedOut,
+            Event::BreakStart => Self::OnBreak,
+        }
+    }
+
+    /// Clocking out while on break is accepted and ends the break as well.
+    pub fn transition(self, event: &Event) -> Result<Self, TransitionError> {
+        match (self, event) {
+            (Self::ClockedOut, Event::ClockIn) => Ok(Self::ClockedIn),
+            (Self::ClockedIn | Self::OnBreak, Event::ClockOut) => Ok(Self::ClockedOut),
+            (Self::ClockedIn, Event::BreakStart) => Ok(Self::OnBreak),
+            (Self::OnBreak, Event::BreakEnd) => Ok(Self::ClockedIn),
+            (Self::ClockedIn | Self::OnBreak, Event::ClockIn) => {
+                Err(TransitionError::AlreadyClockedIn)
+            }
+            (Self::ClockedOut, Event::ClockOut) => Err(TransitionError::AlreadyClockedOut),
+            (Self::ClockedOut, Event::BreakStart | Event::BreakEnd) => {
+                Err(TransitionError::NotClockedIn)
+            }
+            (Self::OnBreak, Event::BreakStart) => Err(TransitionError::AlreadyOnBreak),
+            (Self::ClockedIn, Event::BreakEnd) => Err(TransitionError::NotOnBreak),
+        }
+    }
 }
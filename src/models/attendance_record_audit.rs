@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::{
+    ApiKeyId, AttendanceRecordId, IdType, Timestamp, UserId, WorkplaceId, actor::Channel,
+    attendance_record::Event,
+};
+
+#[derive(Clone, Copy, Deserialize, Serialize, sqlx::Type)]
+#[sqlx(transparent)]
+#[repr(transparent)]
+pub struct AttendanceRecordAuditId(IdType);
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Destroy,
+    Restore,
+}
+
+#[derive(Clone, Deserialize, FromRow, Serialize)]
+pub struct AttendanceRecordAudit {
+    pub id: AttendanceRecordAuditId,
+    pub attendance_record_id: AttendanceRecordId,
+    pub workplace_id: WorkplaceId,
+    pub user_id: UserId,
+    pub action: AuditAction,
+    pub channel: Channel,
+    pub api_key_id: Option<ApiKeyId>,
+    pub previous_event: Option<Event>,
+    pub previous_recorded_at: Option<Timestamp>,
+    pub event: Option<Event>,
+    pub recorded_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+}
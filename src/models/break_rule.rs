@@ -0,0 +1,99 @@
+use std::{fmt, num::ParseIntError};
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Requires at least `break_minutes` of break once the worked time of a day exceeds `threshold_minutes`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BreakRule {
+    pub threshold_minutes: i64,
+    pub break_minutes: i64,
+}
+
+#[derive(Debug, Error)]
+pub enum BreakRuleParseError {
+    #[error("break rule must be written as <threshold minutes>:<break minutes>")]
+    Malformed,
+    #[error("break rule contains an invalid number")]
+    InvalidNumber(#[from] ParseIntError),
+    #[error("break rule must not contain negative minutes")]
+    Negative,
+}
+
+/// Break rules of a workplace, stored and exchanged as e.g. `360:45,480:60`.
+/// An empty string disables the automatic deduction.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct BreakRules(Vec<BreakRule>);
+
+impl BreakRules {
+    /// Break time the rules require for a day with `worked` time, excluding recorded breaks.
+    pub fn required_break(&self, worked: Duration) -> Duration {
+        self.0
+            .iter()
+            .filter(|rule| worked > Duration::minutes(rule.threshold_minutes))
+            .map(|rule| Duration::minutes(rule.break_minutes))
+            .max()
+            .unwrap_or_else(Duration::zero)
+    }
+}
+
+impl Default for BreakRules {
+    // Article 34 of the Labor Standards Act.
+    fn default() -> Self {
+        Self(vec![
+            BreakRule {
+                threshold_minutes: 6 * 60,
+                break_minutes: 45,
+            },
+            BreakRule {
+                threshold_minutes: 8 * 60,
+                break_minutes: 60,
+            },
+        ])
+    }
+}
+
+impl TryFrom<String> for BreakRules {
+    type Error = BreakRuleParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut rules = value
+            .split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .map(|rule| {
+                let (threshold, minutes) = rule.split_once(':').ok_or(Self::Error::Malformed)?;
+                let threshold_minutes = threshold.trim().parse::<i64>()?;
+                let break_minutes = minutes.trim().parse::<i64>()?;
+                if threshold_minutes < 0 || break_minutes < 0 {
+                    return Err(Self::Error::Negative);
+                }
+                Ok(BreakRule {
+                    threshold_minutes,
+                    break_minutes,
+                })
+            })
+            .collect::<Result<Vec<BreakRule>, Self::Error>>()?;
+        rules.sort_by_key(|rule| rule.threshold_minutes);
+        Ok(Self(rules))
+    }
+}
+
+impl From<BreakRules> for String {
+    fn from(value: BreakRules) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for BreakRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules = self
+            .0
+            .iter()
+            .map(|rule| format!("{}:{}", rule.threshold_minutes, rule.break_minutes))
+            .collect::<Vec<String>>();
+        write!(f, "{}", rules.join(","))
+    }
+}
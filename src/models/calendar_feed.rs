@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+use super::{IdType, Timestamp, UserId, WorkplaceId};
+
+#[derive(Clone, Copy, Deserialize, Serialize, sqlx::Type)]
+#[sqlx(transparent)]
+#[repr(transparent)]
+pub struct CalendarFeedId(IdType);
+
+/// Grants read access to the work sessions of a workplace as an iCalendar feed.
+/// Each user has at most one feed per workplace; issuing a new one revokes the previous token.
+#[derive(Clone, Deserialize, FromRow, Serialize)]
+pub struct CalendarFeed {
+    pub id: CalendarFeedId,
+    pub workplace_id: WorkplaceId,
+    pub user_id: UserId,
+    pub digest: String,
+    pub created_at: Timestamp,
+}
@@ -0,0 +1,30 @@
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::{ApiKeyId, Timestamp, UserId};
+
+/// The response to a request sent with an `Idempotency-Key` header, replayed
+/// when the same API key retries the request with the same key. The key is
+/// pending, without a response, while the first request is still running.
+#[derive(Clone, Deserialize, FromRow, Serialize)]
+pub struct IdempotencyKey {
+    pub api_key_id: ApiKeyId,
+    pub user_id: UserId,
+    pub key: String,
+    pub request_uri: String,
+    pub response_status: Option<u16>,
+    pub response_body: Option<String>,
+    pub created_at: Timestamp,
+    pub completed_at: Option<Timestamp>,
+}
+
+impl IdempotencyKey {
+    pub fn is_expired_at(&self, now: &Timestamp, window: TimeDelta) -> bool {
+        **now >= *self.created_at + window
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.completed_at.is_none()
+    }
+}
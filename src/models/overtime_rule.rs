@@ -0,0 +1,70 @@
+use chrono::{ParseWeekdayError, Weekday};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+/// Weekdays on which any work counts as rest-day work, stored and exchanged as e.g. `sat,sun`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RestDays(Vec<Weekday>);
+
+impl RestDays {
+    pub fn contains(&self, weekday: Weekday) -> bool {
+        self.0.contains(&weekday)
+    }
+}
+
+impl Default for RestDays {
+    fn default() -> Self {
+        Self(vec![Weekday::Sun])
+    }
+}
+
+impl TryFrom<String> for RestDays {
+    type Error = ParseWeekdayError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut weekdays = value
+            .split(',')
+            .map(str::trim)
+            .filter(|weekday| !weekday.is_empty())
+            .map(str::parse::<Weekday>)
+            .collect::<Result<Vec<Weekday>, Self::Error>>()?;
+        weekdays.sort_by_key(Weekday::num_days_from_monday);
+        weekdays.dedup();
+        Ok(Self(weekdays))
+    }
+}
+
+impl From<RestDays> for String {
+    fn from(value: RestDays) -> Self {
+        value
+            .0
+            .iter()
+            .map(|weekday| weekday.to_string().to_lowercase())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+/// Thresholds beyond which worked time of a workplace counts as overtime.
+/// Weeks run from Sunday to Saturday.
+#[derive(Clone, Debug, Deserialize, FromRow, PartialEq, Eq, Serialize)]
+pub struct OvertimeRules {
+    #[sqlx(rename = "daily_overtime_threshold_minutes")]
+    pub daily_threshold_minutes: i64,
+    #[sqlx(rename = "weekly_overtime_threshold_minutes")]
+    pub weekly_threshold_minutes: i64,
+    #[sqlx(try_from = "String")]
+    pub rest_days: RestDays,
+}
+
+impl Default for OvertimeRules {
+    // Article 32 of the Labor Standards Act.
+    fn default() -> Self {
+        Self {
+            daily_threshold_minutes: 8 * 60,
+            weekly_threshold_minutes: 40 * 60,
+            rest_days: RestDays::default(),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use thiserror::Error;
+
+use super::{Timestamp, UserId, WorkplaceId};
+
+/// Where a monthly timesheet stands in the approval workflow.
+/// A period nobody has submitted yet is `Open`.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum TimesheetState {
+    Open,
+    Submitted,
+    Approved,
+    Returned,
+}
+
+#[derive(Debug, Error)]
+pub enum TimesheetTransitionError {
+    #[error("already submitted")]
+    AlreadySubmitted,
+
+    #[error("already approved")]
+    AlreadyApproved,
+
+    #[error("not submitted")]
+    NotSubmitted,
+
+    #[error("not approved")]
+    NotApproved,
+}
+
+impl TimesheetTransitionError {
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::AlreadySubmitted => "already-submitted",
+            Self::AlreadyApproved => "already-approved",
+            Self::NotSubmitted => "not-submitted",
+            Self::NotApproved => "not-approved",
+        }
+    }
+}
+
+/// The timesheet of a user for a payroll month of a workplace.
+/// The period is fixed when the timesheet is first saved, so that changing the closing day
+/// later does not move what an approval has locked.
+#[derive(Clone, Deserialize, FromRow, Serialize)]
+pub struct Timesheet {
+    pub workplace_id: WorkplaceId,
+    pub user_id: UserId,
+    pub year: i32,
+    pub month: u32,
+    pub state: TimesheetState,
+    pub comment: Option<String>,
+    pub starts_at: Timestamp,
+    pub ends_at: Timestamp,
+    pub submitted_at: Option<Timestamp>,
+    pub reviewed_by: Option<UserId>,
+    pub reviewed_at: Option<Timestamp>,
+}
+
+impl Timesheet {
+    pub fn open(
+        workplace_id: WorkplaceId,
+        user_id: UserId,
+        year: i32,
+        month: u32,
+        (starts_at, ends_at): (Timestamp, Timestamp),
+    ) -> Self {
+        Self {
+            workplace_id,
+            user_id,
+            year,
+            month,
+            state: TimesheetState::Open,
+            comment: None,
+            starts_at,
+            ends_at,
+            submitted_at: None,
+            reviewed_by: None,
+            reviewed_at: None,
+        }
+    }
+
+    /// Approved timesheets lock the attendance records of their period.
+    pub fn locks_records(&self) -> bool {
+        self.state == TimesheetState::Approved
+    }
+
+    /// Submits an open or returned timesheet, dropping the comment it was returned with.
+    pub fn submit(&mut self) -> Result<(), TimesheetTransitionError> {
+        match self.state {
+            TimesheetState::Open | TimesheetState::Returned => {
+                self.state = TimesheetState::Submitted;
+                self.comment = None;
+                self.submitted_at = Some(Utc::now().into());
+                Ok(())
+            }
+            TimesheetState::Submitted => Err(TimesheetTransitionError::AlreadySubmitted),
+            TimesheetState::Approved => Err(TimesheetTransitionError::AlreadyApproved),
+        }
+    }
+
+    pub fn approve(&mut self, reviewer: UserId) -> Result<(), TimesheetTransitionError> {
+        self.review(
+            TimesheetState::Submitted,
+            TimesheetState::Approved,
+            reviewer,
+            None,
+        )
+    }
+
+    /// Sends a submitted timesheet back to its user, with a comment on what to fix.
+    pub fn send_back(
+        &mut self,
+        reviewer: UserId,
+        comment: String,
+    ) -> Result<(), TimesheetTransitionError> {
+        self.review(
+            TimesheetState::Submitted,
+            TimesheetState::Returned,
+            reviewer,
+            Some(comment),
+        )
+    }
+
+    /// Opens an approved timesheet again, which unlocks its period.
+    pub fn reopen(
+        &mut self,
+        reviewer: UserId,
+        comment: Option<String>,
+    ) -> Result<(), TimesheetTransitionError> {
+        self.review(
+            TimesheetState::Approved,
+            TimesheetState::Open,
+            reviewer,
+            comment,
+        )
+    }
+
+    fn review(
+        &mut self,
+        from: TimesheetState,
+        to: TimesheetState,
+        reviewer: UserId,
+        comment: Option<String>,
+    ) -> Result<(), TimesheetTransitionError> {
+        if self.state != from {
+            return Err(match from {
+                TimesheetState::Approved => TimesheetTransitionError::NotApproved,
+                _ => TimesheetTransitionError::NotSubmitted,
+            });
+        }
+        self.state = to;
+        self.comment = comment;
+        self.reviewed_by = Some(reviewer);
+        self.reviewed_at = Some(Utc::now().into());
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@ use sqlx::prelude::FromRow;
 
 use super::IdType;
 
-#[derive(Clone, Copy, Deserialize, Serialize, sqlx::Type)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Hash, Serialize, sqlx::Type)]
 #[sqlx(transparent)]
 #[repr(transparent)]
 pub struct UserId(IdType);
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+
+use super::{AttendanceRecord, Timestamp, UserId, WorkplaceId, attendance_record::Event};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WorkSessionStatus {
+    Complete,
+    MissingClockIn,
+    MissingClockOut,
+}
+
+/// A break within a work session.
+/// A break still open at clock-out ends with the clock-out.
+#[derive(Clone)]
+pub struct BreakPeriod {
+    pub started_at: Timestamp,
+    pub ended_at: Option<Timestamp>,
+}
+
+impl BreakPeriod {
+    pub fn duration(&self) -> Option<TimeDelta> {
+        self.ended_at
+            .as_ref()
+            .map(|ended_at| **ended_at - *self.started_at)
+    }
+}
+
+#[derive(Clone)]
+pub struct WorkSession {
+    pub workplace_id: WorkplaceId,
+    pub user_id: UserId,
+    pub clock_in: Option<AttendanceRecord>,
+    pub clock_out: Option<AttendanceRecord>,
+    pub breaks: Vec<BreakPeriod>,
+}
+
+struct OpenSession<'a> {
+    clock_in: &'a AttendanceRecord,
+    breaks: Vec<BreakPeriod>,
+}
+
+impl<'a> OpenSession<'a> {
+    fn new(clock_in: &'a AttendanceRecord) -> Self {
+        Self {
+            clock_in,
+            breaks: Vec::new(),
+        }
+    }
+
+    fn open_break(&mut self) -> Option<&mut BreakPeriod> {
+        self.breaks
+            .last_mut()
+            .filter(|break_period| break_period.ended_at.is_none())
+    }
+
+    fn start_break(&mut self, record: &AttendanceRecord) {
+        if self.open_break().is_none() {
+            self.breaks.push(BreakPeriod {
+                started_at: record.recorded_at.clone(),
+                ended_at: None,
+            });
+        }
+    }
+
+    fn end_break(&mut self, record: &AttendanceRecord) {
+        if let Some(break_period) = self.open_break() {
+            break_period.ended_at = Some(record.recorded_at.clone());
+        }
+    }
+
+    fn close(mut self, clock_out: Option<&AttendanceRecord>) -> WorkSession {
+        if let Some(clock_out) = clock_out {
+            self.end_break(clock_out);
+        }
+        WorkSession {
+            workplace_id: self.clock_in.workplace_id,
+            user_id: self.clock_in.user_id,
+            clock_in: Some(self.clock_in.clone()),
+            clock_out: clock_out.cloned(),
+            breaks: self.breaks,
+        }
+    }
+}
+
+fn session_key(record: &AttendanceRecord) -> (WorkplaceId, UserId) {
+    (record.workplace_id, record.user_id)
+}
+
+impl WorkSession {
+    /// Pairs each clock-in with the following clock-out of the same user in the same workplace,
+    /// collecting the breaks recorded in between.
+    /// Records are expected to be ordered by `recorded_at`.
+    /// A clock-in followed by another clock-in, or a clock-out without a preceding clock-in,
+    /// ends up in a session of its own which is flagged by `status`.
+    /// Break events outside of a session are ignored.
+    pub fn pair(records: &[AttendanceRecord]) -> Vec<WorkSession> {
+        let mut sessions = Vec::new();
+        let mut open_sessions: HashMap<(WorkplaceId, UserId), OpenSession> = HashMap::new();
+
+        for record in records {
+            match record.event {
+                Event::ClockIn => {
+                    let open_session = OpenSession::new(record);
+                    if let Some(unclosed) = open_sessions.insert(session_key(record), open_session)
+                    {
+                        sessions.push(unclosed.close(None));
+                    }
+                }
+                Event::ClockOut => match open_sessions.remove(&session_key(record)) {
+                    Some(open_session) => sessions.push(open_session.close(Some(record))),
+                    None => sessions.push(Self::without_clock_in(record)),
+                },
+                Event::BreakStart => {
+                    if let Some(open_session) = open_sessions.get_mut(&session_key(record)) {
+                        open_session.start_break(record);
+                    }
+                }
+                Event::BreakEnd => {
+                    if let Some(open_session) = open_sessions.get_mut(&session_key(record)) {
+                        open_session.end_break(record);
+                    }
+                }
+            }
+        }
+        sessions.extend(
+            open_sessions
+                .into_values()
+                .map(|open_session| open_session.close(None)),
+        );
+
+        sessions.sort_by(|a, b| a.recorded_at().cmp(b.recorded_at()));
+        sessions
+    }
+
+    fn without_clock_in(clock_out: &AttendanceRecord) -> Self {
+        Self {
+            workplace_id: clock_out.workplace_id,
+            user_id: clock_out.user_id,
+            clock_in: None,
+            clock_out: Some(clock_out.clone()),
+            breaks: Vec::new(),
+        }
+    }
+
+    pub fn started_at(&self) -> Option<&Timestamp> {
+        self.clock_in.as_ref().map(|record| &record.recorded_at)
+    }
+
+    pub fn ended_at(&self) -> Option<&Timestamp> {
+        self.clock_out.as_ref().map(|record| &record.recorded_at)
+    }
+
+    pub fn status(&self) -> WorkSessionStatus {
+        match (&self.clock_in, &self.clock_out) {
+            (Some(_), Some(_)) => WorkSessionStatus::Complete,
+            (Some(_), None) => WorkSessionStatus::MissingClockOut,
+            _ => WorkSessionStatus::MissingClockIn,
+        }
+    }
+
+    /// Time between clock-in and clock-out, including breaks.
+    pub fn duration(&self) -> Option<TimeDelta> {
+        match (self.started_at(), self.ended_at()) {
+            (Some(start), Some(end)) => Some(**end - **start),
+            _ => None,
+        }
+    }
+
+    /// Total time of the breaks which have ended.
+    pub fn break_duration(&self) -> TimeDelta {
+        self.breaks.iter().filter_map(BreakPeriod::duration).sum()
+    }
+
+    /// Time between clock-in and clock-out, excluding breaks.
+    pub fn worked_duration(&self) -> Option<TimeDelta> {
+        self.duration()
+            .map(|duration| duration - self.break_duration())
+    }
+
+    /// Periods between clock-in and clock-out with the breaks cut out.
+    /// Empty unless the session is complete.
+    pub fn worked_periods(&self) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let (Some(start), Some(end)) = (self.started_at(), self.ended_at()) else {
+            return Vec::new();
+        };
+
+        let mut periods = Vec::new();
+        let mut cursor = **start;
+        for break_period in &self.breaks {
+            let Some(break_end) = &break_period.ended_at else {
+                continue;
+            };
+            if *break_period.started_at > cursor {
+                periods.push((cursor, *break_period.started_at));
+            }
+            cursor = cursor.max(**break_end);
+        }
+        if **end > cursor {
+            periods.push((cursor, **end));
+        }
+        periods
+    }
+
+    fn recorded_at(&self) -> &Timestamp {
+        self.started_at().or(self.ended_at()).unwrap()
+    }
+}
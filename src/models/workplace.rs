@@ -1,16 +1,108 @@
+use std::{fmt, ops::Deref};
+
+use chrono_tz::{Asia, ParseError, Tz};
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 
-use super::{IdType, UserId};
+use super::{BreakRules, IdType, OvertimeRules, Timestamp, UserId, Viewer};
 
-#[derive(Clone, Copy, Deserialize, Serialize, sqlx::Type)]
+#[derive(
+    Clone, Copy, Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, sqlx::Type,
+)]
 #[sqlx(transparent)]
 #[repr(transparent)]
 pub struct WorkplaceId(IdType);
 
+impl fmt::Display for WorkplaceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<IdType> for WorkplaceId {
+    fn from(value: IdType) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+#[repr(transparent)]
+pub struct Timezone(Tz);
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Self(Asia::Tokyo)
+    }
+}
+
+impl TryFrom<String> for Timezone {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse::<Tz>().map(Self)
+    }
+}
+
+impl From<Timezone> for String {
+    fn from(value: Timezone) -> Self {
+        value.0.name().to_owned()
+    }
+}
+
+impl Deref for Timezone {
+    type Target = Tz;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Day of the month on which the payroll period closes.
+/// A day past the end of a month closes on its last day, so 31 always means the month end.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize, sqlx::Type)]
+#[serde(try_from = "u32")]
+#[sqlx(transparent)]
+#[repr(transparent)]
+pub struct ClosingDay(u32);
+
+impl ClosingDay {
+    pub fn day(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Default for ClosingDay {
+    fn default() -> Self {
+        Self(31)
+    }
+}
+
+impl TryFrom<u32> for ClosingDay {
+    type Error = &'static str;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        if (1..=31).contains(&value) {
+            Ok(Self(value))
+        } else {
+            Err("closing day must be between 1 and 31")
+        }
+    }
+}
+
 #[derive(Clone, Deserialize, FromRow, Serialize)]
 pub struct Workplace {
     pub id: WorkplaceId,
     pub user_id: UserId,
     pub name: String,
+    #[sqlx(try_from = "String")]
+    pub timezone: Timezone,
+    #[sqlx(try_from = "String")]
+    pub break_rules: BreakRules,
+    #[sqlx(flatten)]
+    pub overtime_rules: OvertimeRules,
+    pub closing_day: ClosingDay,
+    pub archived_at: Option<Timestamp>,
+    #[sqlx(flatten)]
+    pub viewer: Viewer,
 }
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+use super::{UserId, WorkplaceId};
+
+/// Role of a user in a workplace.
+/// The owner is the user the workplace belongs to; everyone else joins through a membership.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    Owner,
+    Manager,
+    Member,
+}
+
+impl Role {
+    /// Owners and managers view and correct the attendance of everyone in the workplace,
+    /// while members only see their own.
+    pub fn manages_attendance(self) -> bool {
+        matches!(self, Self::Owner | Self::Manager)
+    }
+
+    /// Only the owner changes the settings and the memberships of the workplace.
+    pub fn manages_workplace(self) -> bool {
+        self == Self::Owner
+    }
+}
+
+#[derive(Clone, Deserialize, FromRow, Serialize)]
+pub struct WorkplaceMembership {
+    pub workplace_id: WorkplaceId,
+    pub user_id: UserId,
+    pub role: Role,
+}
+
+/// The user a workplace was looked up for, together with their role in it.
+/// Repositories scope every query on the workplace to what this user may see.
+#[derive(Clone, Deserialize, FromRow, Serialize)]
+pub struct Viewer {
+    #[sqlx(rename = "viewer_id")]
+    pub user_id: UserId,
+    #[sqlx(rename = "viewer_role")]
+    pub role: Role,
+}
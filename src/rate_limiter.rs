@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::config::RateLimitBucketConfig;
+use crate::models::ApiKeyId;
+
+// expired windows are swept once this many buckets are tracked
+const SWEEP_THRESHOLD: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BucketKey {
+    ApiKey(ApiKeyId),
+    Ip(IpAddr),
+}
+
+struct Window {
+    started_at: Instant,
+    length: Duration,
+    count: u32,
+}
+
+impl Window {
+    fn is_over(&self, now: Instant) -> bool {
+        now >= self.started_at + self.length
+    }
+
+    fn resets_in(&self, now: Instant) -> Duration {
+        (self.started_at + self.length).saturating_duration_since(now)
+    }
+}
+
+/// State of one bucket after a request was counted against it.
+#[derive(Clone, Copy, Debug)]
+pub struct BucketStatus {
+    pub limit: u32,
+    pub remaining: u32,
+    pub resets_in: Duration,
+}
+
+impl BucketStatus {
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+pub enum RateLimitDecision {
+    /// The request was counted; carries the status of the tightest bucket, if any.
+    Allowed(Option<BucketStatus>),
+    /// The request was refused without being counted; carries the exhausted bucket.
+    Limited(BucketStatus),
+}
+
+/// Fixed-window request counters shared by every worker.
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    windows: Arc<Mutex<HashMap<BucketKey, Window>>>,
+}
+
+impl RateLimiter {
+    /// Counts a request against every bucket, unless one of them is already exhausted.
+    pub fn check(
+        &self,
+        buckets: &[(BucketKey, &RateLimitBucketConfig)],
+        now: Instant,
+    ) -> RateLimitDecision {
+        let mut windows = self.windows.lock().unwrap();
+        if windows.len() >= SWEEP_THRESHOLD {
+            windows.retain(|_, window| !window.is_over(now));
+        }
+
+        let mut statuses = Vec::with_capacity(buckets.len());
+        for (key, config) in buckets {
+            let window = windows.entry(*key).or_insert_with(|| Window {
+                started_at: now,
+                length: Duration::from_secs(config.window_seconds),
+                count: 0,
+            });
+            if window.is_over(now) {
+                window.started_at = now;
+                window.length = Duration::from_secs(config.window_seconds);
+                window.count = 0;
+            }
+            let status = BucketStatus {
+                limit: config.requests,
+                remaining: config.requests.saturating_sub(window.count),
+                resets_in: window.resets_in(now),
+            };
+            if status.is_exhausted() {
+                return RateLimitDecision::Limited(status);
+            }
+            statuses.push(status);
+        }
+
+        for (key, _) in buckets {
+            if let Some(window) = windows.get_mut(key) {
+                window.count += 1;
+            }
+        }
+        let tightest = statuses
+            .into_iter()
+            .map(|status| BucketStatus {
+                remaining: status.remaining - 1,
+                ..status
+            })
+            .min_by_key(|status| status.remaining);
+        RateLimitDecision::Allowed(tightest)
+    }
+}
@@ -4,25 +4,69 @@ use sqlx::{Pool, Sqlite};
 use crate::{
     errors::DatabaseError,
     models::{
-        ApiKey, ApiKeyId, AttendanceRecord, AttendanceRecordId, Timestamp, User, UserId, Workplace,
-        WorkplaceId, attendance_record::Event,
+        Actor, ApiKey, ApiKeyId, ApiKeyScope, AttendanceRecord, AttendanceRecordAudit,
+        AttendanceRecordId, AuditAction, BreakRules, CalendarFeed, IdempotencyKey, Role, Timesheet,
+        Timestamp, Timezone, TokenDigest, User, UserId, Workplace, WorkplaceId,
+        WorkplaceMembership,
+        attendance_record::{Event, PlacementError},
     },
     repositories::{
         api_key::RdbApiKeyRepository, attendance_record::RdbAttendanceRecordRepository,
-        user::RdbUserRepository, workplace::RdbWorkplaceRepository,
+        attendance_record_audit::RdbAttendanceRecordAuditRepository,
+        audited_attendance_record::AuditedAttendanceRecordRepository,
+        calendar_feed::RdbCalendarFeedRepository, idempotency_key::RdbIdempotencyKeyRepository,
+        timesheet::RdbTimesheetRepository, user::RdbUserRepository,
+        workplace::RdbWorkplaceRepository, workplace_membership::RdbWorkplaceMembershipRepository,
     },
 };
 
 mod api_key;
 mod attendance_record;
+mod attendance_record_audit;
+mod audited_attendance_record;
+mod calendar_feed;
+mod idempotency_key;
+mod timesheet;
 mod user;
 mod workplace;
+mod workplace_membership;
 
 #[async_trait]
 pub trait ApiKeyRepository {
     async fn list(&self, user: &User) -> Result<Vec<ApiKey>, DatabaseError>;
-    async fn find_by_digest(&self, digest: &str) -> Result<Option<ApiKey>, DatabaseError>;
-    async fn create(&self, user: &User, name: &str, digest: &str) -> Result<ApiKey, DatabaseError>;
+    async fn find_by_public_id(&self, public_id: &str) -> Result<Option<ApiKey>, DatabaseError>;
+    /// Finds the key matching any of `digests`, trying them in order.
+    async fn find_by_digest(
+        &self,
+        digests: &[TokenDigest],
+    ) -> Result<Option<ApiKey>, DatabaseError>;
+    async fn create(
+        &self,
+        user: &User,
+        name: &str,
+        public_id: &str,
+        digest: &TokenDigest,
+        scope: &ApiKeyScope,
+        expires_at: Option<&Timestamp>,
+    ) -> Result<ApiKey, DatabaseError>;
+    async fn find(&self, user: &User, id: &ApiKeyId) -> Result<ApiKey, DatabaseError>;
+    /// Replaces the token of the key, keeping the old one valid until `previous_digest_expires_at`.
+    async fn rotate(
+        &self,
+        api_key: &ApiKey,
+        public_id: &str,
+        digest: &TokenDigest,
+        previous_digest_expires_at: &Timestamp,
+    ) -> Result<ApiKey, DatabaseError>;
+    async fn update_digest(&self, id: &ApiKeyId, digest: &TokenDigest)
+    -> Result<(), DatabaseError>;
+    /// Remembers when and from which address the key last authenticated a request.
+    async fn record_usage(
+        &self,
+        id: &ApiKeyId,
+        used_at: &Timestamp,
+        ip: Option<&str>,
+    ) -> Result<(), DatabaseError>;
     async fn destroy(&self, user: &User, id: &ApiKeyId) -> Result<(), DatabaseError>;
 }
 
@@ -34,17 +78,146 @@ pub trait AttendanceRecordRepository {
         event: &Event,
         datetime: &Timestamp,
     ) -> Result<AttendanceRecord, DatabaseError>;
+    /// Creates the record unless an approved timesheet locks `datetime` or it does not fit between
+    /// the records of the viewer right before and after it. The check and the insert run in one
+    /// transaction holding the write lock, so that concurrent calls are placed one after another.
+    async fn create_in_sequence(
+        &self,
+        workplace: &Workplace,
+        event: &Event,
+        datetime: &Timestamp,
+    ) -> Result<Result<AttendanceRecord, PlacementError>, DatabaseError>;
+    /// Creates a record for each of `entries` in one transaction, in the given order.
+    async fn create_many(
+        &self,
+        workplace: &Workplace,
+        entries: &[(Event, Timestamp)],
+    ) -> Result<Vec<AttendanceRecord>, DatabaseError>;
+    async fn find(
+        &self,
+        workplace: &Workplace,
+        id: AttendanceRecordId,
+    ) -> Result<AttendanceRecord, DatabaseError>;
+    async fn update(
+        &self,
+        workplace: &Workplace,
+        attendance_record: &AttendanceRecord,
+    ) -> Result<AttendanceRecord, DatabaseError>;
     async fn destroy(
         &self,
         workplace: &Workplace,
         id: AttendanceRecordId,
     ) -> Result<(), DatabaseError>;
+    async fn restore(
+        &self,
+        workplace: &Workplace,
+        id: AttendanceRecordId,
+    ) -> Result<AttendanceRecord, DatabaseError>;
+    async fn find_deleted(
+        &self,
+        workplace: &Workplace,
+        id: AttendanceRecordId,
+    ) -> Result<AttendanceRecord, DatabaseError>;
+    async fn list_deleted(
+        &self,
+        workplace: &Workplace,
+    ) -> Result<Vec<AttendanceRecord>, DatabaseError>;
+    async fn purge_deleted(&self, deleted_before: &Timestamp) -> Result<u64, DatabaseError>;
     async fn list(
         &self,
         workplace: &Workplace,
         start_time: &Timestamp,
         end_time: &Timestamp,
     ) -> Result<Vec<AttendanceRecord>, DatabaseError>;
+    /// The latest record of `user_id` at or before `datetime`, leaving out the `excluding` one.
+    async fn find_previous(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        datetime: &Timestamp,
+        excluding: Option<AttendanceRecordId>,
+    ) -> Result<Option<AttendanceRecord>, DatabaseError>;
+    /// The earliest record of `user_id` after `datetime`, leaving out the `excluding` one.
+    async fn find_next(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        datetime: &Timestamp,
+        excluding: Option<AttendanceRecordId>,
+    ) -> Result<Option<AttendanceRecord>, DatabaseError>;
+}
+
+#[async_trait]
+pub trait AttendanceRecordAuditRepository {
+    async fn create(
+        &self,
+        actor: &Actor,
+        action: AuditAction,
+        previous: Option<&AttendanceRecord>,
+        current: Option<&AttendanceRecord>,
+    ) -> Result<(), DatabaseError>;
+    async fn list(
+        &self,
+        workplace: &Workplace,
+        attendance_record_id: AttendanceRecordId,
+    ) -> Result<Vec<AttendanceRecordAudit>, DatabaseError>;
+}
+
+#[async_trait]
+pub trait CalendarFeedRepository {
+    async fn find(&self, workplace: &Workplace) -> Result<Option<CalendarFeed>, DatabaseError>;
+    async fn find_by_digest(&self, digest: &str) -> Result<Option<CalendarFeed>, DatabaseError>;
+    async fn create(
+        &self,
+        workplace: &Workplace,
+        digest: &str,
+    ) -> Result<CalendarFeed, DatabaseError>;
+    async fn destroy(&self, workplace: &Workplace) -> Result<(), DatabaseError>;
+}
+
+#[async_trait]
+pub trait IdempotencyKeyRepository {
+    async fn find(
+        &self,
+        api_key_id: ApiKeyId,
+        key: &str,
+    ) -> Result<Option<IdempotencyKey>, DatabaseError>;
+    /// Inserts the key as pending, unless it is already taken, which is told by returning `false`.
+    async fn reserve(&self, idempotency_key: &IdempotencyKey) -> Result<bool, DatabaseError>;
+    /// Stores the response of a pending key. A completed key is never overwritten.
+    async fn complete(&self, idempotency_key: &IdempotencyKey) -> Result<(), DatabaseError>;
+    /// Gives up a pending key, so that the request can be retried with it.
+    async fn release(&self, idempotency_key: &IdempotencyKey) -> Result<(), DatabaseError>;
+    async fn purge_expired(&self, created_before: &Timestamp) -> Result<u64, DatabaseError>;
+}
+
+#[async_trait]
+pub trait TimesheetRepository {
+    async fn list(
+        &self,
+        workplace: &Workplace,
+        year: Option<i32>,
+    ) -> Result<Vec<Timesheet>, DatabaseError>;
+    async fn find(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        year: i32,
+        month: u32,
+    ) -> Result<Option<Timesheet>, DatabaseError>;
+    /// The approved timesheet of `user_id` whose period contains `datetime`, if any.
+    async fn find_locking(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        datetime: &Timestamp,
+    ) -> Result<Option<Timesheet>, DatabaseError>;
+    /// Inserts the timesheet, or updates its state on an existing one for the same period.
+    async fn save(
+        &self,
+        workplace: &Workplace,
+        timesheet: &Timesheet,
+    ) -> Result<Timesheet, DatabaseError>;
 }
 
 #[async_trait]
@@ -54,16 +227,66 @@ pub trait UserRepository {
 
 #[async_trait]
 pub trait WorkplaceRepository {
-    async fn list(&self, user: &User) -> Result<Vec<Workplace>, DatabaseError>;
-    async fn create(&self, user: &User, name: &str) -> Result<Workplace, DatabaseError>;
+    async fn list(
+        &self,
+        user: &User,
+        including_archived: bool,
+    ) -> Result<Vec<Workplace>, DatabaseError>;
+    async fn create(
+        &self,
+        user: &User,
+        name: &str,
+        timezone: &Timezone,
+        break_rules: &BreakRules,
+    ) -> Result<Workplace, DatabaseError>;
     async fn find(&self, user: &User, id: WorkplaceId) -> Result<Workplace, DatabaseError>;
+    /// Only the owner may change a workplace, so it is not found for anyone else.
+    async fn update(&self, workplace: &Workplace) -> Result<Workplace, DatabaseError>;
+    /// Deletes the workplace together with its timesheets, calendar feeds and memberships in one
+    /// transaction. Like `update`, it is not found for anyone but the owner.
+    /// Its attendance records and their audit trail go as well with `including_attendance_records`,
+    /// and otherwise nothing is deleted while any are left, which is told by returning `false`.
+    async fn delete_cascading(
+        &self,
+        workplace: &Workplace,
+        including_attendance_records: bool,
+    ) -> Result<bool, DatabaseError>;
+}
+
+/// Memberships other than the owner's are stored; the owner is listed first with `Role::Owner`.
+/// Only the owner may change them, and members see nobody but themselves.
+#[async_trait]
+pub trait WorkplaceMembershipRepository {
+    async fn list(&self, workplace: &Workplace) -> Result<Vec<WorkplaceMembership>, DatabaseError>;
+    async fn create(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        role: Role,
+    ) -> Result<WorkplaceMembership, DatabaseError>;
+    async fn update(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        role: Role,
+    ) -> Result<WorkplaceMembership, DatabaseError>;
+    async fn destroy(&self, workplace: &Workplace, user_id: UserId) -> Result<(), DatabaseError>;
 }
 
 pub trait RepositoryFactory {
     fn api_key(&self) -> Box<dyn ApiKeyRepository + '_>;
     fn attendance_record(&self) -> Box<dyn AttendanceRecordRepository + '_>;
+    fn audited_attendance_record<'a>(
+        &'a self,
+        actor: &'a Actor,
+    ) -> Box<dyn AttendanceRecordRepository + 'a>;
+    fn attendance_record_audit(&self) -> Box<dyn AttendanceRecordAuditRepository + '_>;
+    fn calendar_feed(&self) -> Box<dyn CalendarFeedRepository + '_>;
+    fn idempotency_key(&self) -> Box<dyn IdempotencyKeyRepository + '_>;
+    fn timesheet(&self) -> Box<dyn TimesheetRepository + '_>;
     fn user(&self) -> Box<dyn UserRepository + '_>;
     fn workplace(&self) -> Box<dyn WorkplaceRepository + '_>;
+    fn workplace_membership(&self) -> Box<dyn WorkplaceMembershipRepository + '_>;
 }
 
 #[derive(Clone)]
@@ -86,6 +309,29 @@ impl RepositoryFactory for RdbRepositories {
         Box::new(RdbAttendanceRecordRepository::new(&self.pool))
     }
 
+    fn audited_attendance_record<'a>(
+        &'a self,
+        actor: &'a Actor,
+    ) -> Box<dyn AttendanceRecordRepository + 'a> {
+        Box::new(AuditedAttendanceRecordRepository::new(&self.pool, actor))
+    }
+
+    fn attendance_record_audit(&self) -> Box<dyn AttendanceRecordAuditRepository + '_> {
+        Box::new(RdbAttendanceRecordAuditRepository::new(&self.pool))
+    }
+
+    fn calendar_feed(&self) -> Box<dyn CalendarFeedRepository + '_> {
+        Box::new(RdbCalendarFeedRepository::new(&self.pool))
+    }
+
+    fn idempotency_key(&self) -> Box<dyn IdempotencyKeyRepository + '_> {
+        Box::new(RdbIdempotencyKeyRepository::new(&self.pool))
+    }
+
+    fn timesheet(&self) -> Box<dyn TimesheetRepository + '_> {
+        Box::new(RdbTimesheetRepository::new(&self.pool))
+    }
+
     fn user(&self) -> Box<dyn UserRepository + '_> {
         Box::new(RdbUserRepository::new(&self.pool))
     }
@@ -93,4 +339,8 @@ impl RepositoryFactory for RdbRepositories {
     fn workplace(&self) -> Box<dyn WorkplaceRepository + '_> {
         Box::new(RdbWorkplaceRepository::new(&self.pool))
     }
+
+    fn workplace_membership(&self) -> Box<dyn WorkplaceMembershipRepository + '_> {
+        Box::new(RdbWorkplaceMembershipRepository::new(&self.pool))
+    }
 }
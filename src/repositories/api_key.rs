@@ -7,7 +7,7 @@ use sqlx::{Executor, Sqlite};
 use super::ApiKeyRepository;
 use crate::{
     errors::DatabaseError,
-    models::{ApiKey, ApiKeyId, User},
+    models::{ApiKey, ApiKeyId, ApiKeyScope, Timestamp, TokenDigest, User},
 };
 
 pub struct RdbApiKeyRepository<'a, T: Executor<'a>> {
@@ -33,7 +33,7 @@ where
     T: Executor<'a, Database = Sqlite> + Copy + Sync,
 {
     async fn list(&self, user: &User) -> Result<Vec<ApiKey>, DatabaseError> {
-        let api_keys: Vec<ApiKey> = sqlx::query_as("select id, user_id, name, digest, created_at from api_keys where user_id = $1 order by created_at desc")
+        let api_keys: Vec<ApiKey> = sqlx::query_as("select id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at from api_keys where user_id = $1 order by created_at desc")
             .bind(user.id)
             .fetch_all(self.executor)
             .await
@@ -42,11 +42,11 @@ where
         Ok(api_keys)
     }
 
-    async fn find_by_digest(&self, digest: &str) -> Result<Option<ApiKey>, DatabaseError> {
+    async fn find_by_public_id(&self, public_id: &str) -> Result<Option<ApiKey>, DatabaseError> {
         let result: Option<ApiKey> = sqlx::query_as(
-            "select id, user_id, name, digest, created_at from api_keys where digest = $1",
+            "select id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at from api_keys where public_id = $1",
         )
-        .bind(digest)
+        .bind(public_id)
         .fetch_optional(self.executor)
         .await
         .inspect_err(|e| log::error!("Failed to query api_keys: {:?}", e))?;
@@ -54,19 +54,115 @@ where
         Ok(result)
     }
 
-    async fn create(&self, user: &User, name: &str, digest: &str) -> Result<ApiKey, DatabaseError> {
-        let statement = "insert into api_keys (user_id, name, digest, created_at) values ($1, $2, $3, $4) returning id, user_id, name, digest, created_at";
+    async fn find_by_digest(
+        &self,
+        digests: &[TokenDigest],
+    ) -> Result<Option<ApiKey>, DatabaseError> {
+        for digest in digests {
+            let result: Option<ApiKey> = sqlx::query_as(
+            "select id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at from api_keys where digest = $1 or previous_digest = $1",
+        )
+        .bind(&digest.value)
+        .fetch_optional(self.executor)
+        .await
+        .inspect_err(|e| log::error!("Failed to query api_keys: {:?}", e))?;
+            if result.is_some() {
+                return Ok(result);
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn create(
+        &self,
+        user: &User,
+        name: &str,
+        public_id: &str,
+        digest: &TokenDigest,
+        scope: &ApiKeyScope,
+        expires_at: Option<&Timestamp>,
+    ) -> Result<ApiKey, DatabaseError> {
+        let statement = "insert into api_keys (user_id, name, public_id, digest, digest_key_id, created_at, workplace_ids, actions, expires_at) values ($1, $2, $3, $4, $5, $6, $7, $8, $9) returning id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at";
         let now = Utc::now();
         let api_key: ApiKey = sqlx::query_as(statement)
             .bind(user.id)
             .bind(name)
-            .bind(digest)
+            .bind(public_id)
+            .bind(&digest.value)
+            .bind(&digest.key_id)
             .bind(now)
+            .bind(String::from(scope.workplace_ids.clone()))
+            .bind(String::from(scope.actions.clone()))
+            .bind(expires_at)
             .fetch_one(self.executor)
             .await?;
         Ok(api_key)
     }
 
+    async fn find(&self, user: &User, id: &ApiKeyId) -> Result<ApiKey, DatabaseError> {
+        let api_key: ApiKey = sqlx::query_as("select id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at from api_keys where user_id = $1 and id = $2")
+            .bind(user.id)
+            .bind(id)
+            .fetch_one(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to query api_keys: {:?}", e))?;
+
+        Ok(api_key)
+    }
+
+    async fn rotate(
+        &self,
+        api_key: &ApiKey,
+        public_id: &str,
+        digest: &TokenDigest,
+        previous_digest_expires_at: &Timestamp,
+    ) -> Result<ApiKey, DatabaseError> {
+        let statement = "update api_keys set public_id = $1, digest = $2, digest_key_id = $3, previous_digest = digest, previous_digest_expires_at = $4 where user_id = $5 and id = $6 returning id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at";
+        let api_key: ApiKey = sqlx::query_as(statement)
+            .bind(public_id)
+            .bind(&digest.value)
+            .bind(&digest.key_id)
+            .bind(previous_digest_expires_at)
+            .bind(api_key.user_id)
+            .bind(api_key.id)
+            .fetch_one(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to rotate api_key: {:?}", e))?;
+        Ok(api_key)
+    }
+
+    async fn update_digest(
+        &self,
+        id: &ApiKeyId,
+        digest: &TokenDigest,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("update api_keys set digest = $1, digest_key_id = $2 where id = $3")
+            .bind(&digest.value)
+            .bind(&digest.key_id)
+            .bind(id)
+            .execute(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to update api_key: {:?}", e))?;
+        Ok(())
+    }
+
+    async fn record_usage(
+        &self,
+        id: &ApiKeyId,
+        used_at: &Timestamp,
+        ip: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("update api_keys set last_used_at = $1, last_used_ip = $2 where id = $3")
+            .bind(used_at)
+            .bind(ip)
+            .bind(id)
+            .execute(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to update api_key: {:?}", e))?;
+        Ok(())
+    }
+
     async fn destroy(&self, user: &User, id: &ApiKeyId) -> Result<(), DatabaseError> {
         sqlx::query("delete from api_keys where user_id = $1 and id = $2")
             .bind(user.id)
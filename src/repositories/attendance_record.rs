@@ -2,16 +2,20 @@ use std::marker::PhantomData;
 
 use async_trait::async_trait;
 use chrono::Utc;
-use sqlx::{Executor, Sqlite};
+use sqlx::{Acquire, Connection, Executor, Sqlite, SqliteConnection};
 
+use super::timesheet;
 use crate::{
     errors::DatabaseError,
     models::{
-        AttendanceRecord, AttendanceRecordId, Timestamp, Workplace, attendance_record::Event,
+        AttendanceRecord, AttendanceRecordId, Timestamp, UserId, Workplace,
+        attendance_record::{Event, Placement, PlacementError},
     },
     repositories::AttendanceRecordRepository,
 };
 
+/// Records of a workplace are visible to its owner and managers, and to the member who made
+/// them. Every query below binds the viewer of `workplace` to restrict itself accordingly.
 pub struct RdbAttendanceRecordRepository<'a, T: Executor<'a>> {
     executor: T,
     _marker: PhantomData<&'a T>,
@@ -32,7 +36,7 @@ where
 #[async_trait]
 impl<'a, T> AttendanceRecordRepository for RdbAttendanceRecordRepository<'a, T>
 where
-    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+    T: Executor<'a, Database = Sqlite> + Acquire<'a, Database = Sqlite> + Copy + Sync,
 {
     async fn create(
         &self,
@@ -40,34 +44,131 @@ where
         event: &Event,
         datetime: &Timestamp,
     ) -> Result<AttendanceRecord, DatabaseError> {
-        let statement = "insert into attendance_records (workplace_id, event, recorded_at, created_at) values ($1, $2, $3, $4) returning id, workplace_id, event, recorded_at";
-        let now = Utc::now();
-        let attendance_record = sqlx::query_as(statement)
-            .bind(workplace.id)
-            .bind(event)
-            .bind(datetime)
-            .bind(now)
-            .fetch_one(self.executor)
+        insert(self.executor, workplace, event, datetime).await
+    }
+
+    async fn create_in_sequence(
+        &self,
+        workplace: &Workplace,
+        event: &Event,
+        datetime: &Timestamp,
+    ) -> Result<Result<AttendanceRecord, PlacementError>, DatabaseError> {
+        let mut conn = self
+            .executor
+            .acquire()
             .await
-            .inspect_err(|e| log::error!("Failed to create attendance_record: {:?}", e))?;
+            .inspect_err(|e| log::error!("Failed to acquire connection: {:?}", e))?;
+        let mut tx = conn
+            .begin_with(BEGIN_IMMEDIATE)
+            .await
+            .inspect_err(|e| log::error!("Failed to begin transaction: {:?}", e))?;
 
+        let attendance_record = insert_in_sequence(&mut tx, workplace, event, datetime).await?;
+        if attendance_record.is_ok() {
+            tx.commit()
+                .await
+                .inspect_err(|e| log::error!("Failed to commit transaction: {:?}", e))?;
+        }
         Ok(attendance_record)
     }
 
+    async fn create_many(
+        &self,
+        workplace: &Workplace,
+        entries: &[(Event, Timestamp)],
+    ) -> Result<Vec<AttendanceRecord>, DatabaseError> {
+        let mut tx = self
+            .executor
+            .begin()
+            .await
+            .inspect_err(|e| log::error!("Failed to begin transaction: {:?}", e))?;
+
+        let mut attendance_records = Vec::with_capacity(entries.len());
+        for (event, datetime) in entries {
+            let attendance_record = insert(&mut *tx, workplace, event, datetime).await?;
+            attendance_records.push(attendance_record);
+        }
+        tx.commit()
+            .await
+            .inspect_err(|e| log::error!("Failed to commit transaction: {:?}", e))?;
+
+        Ok(attendance_records)
+    }
+
+    async fn find(
+        &self,
+        workplace: &Workplace,
+        id: AttendanceRecordId,
+    ) -> Result<AttendanceRecord, DatabaseError> {
+        find(self.executor, workplace, id).await
+    }
+
+    async fn update(
+        &self,
+        workplace: &Workplace,
+        attendance_record: &AttendanceRecord,
+    ) -> Result<AttendanceRecord, DatabaseError> {
+        update(self.executor, workplace, attendance_record).await
+    }
+
     async fn destroy(
         &self,
         workplace: &Workplace,
         id: AttendanceRecordId,
     ) -> Result<(), DatabaseError> {
-        let statement = "delete from attendance_records where id = $1 and workplace_id = $2";
-        sqlx::query(statement)
+        soft_delete(self.executor, workplace, id).await
+    }
+
+    async fn restore(
+        &self,
+        workplace: &Workplace,
+        id: AttendanceRecordId,
+    ) -> Result<AttendanceRecord, DatabaseError> {
+        restore(self.executor, workplace, id).await
+    }
+
+    async fn find_deleted(
+        &self,
+        workplace: &Workplace,
+        id: AttendanceRecordId,
+    ) -> Result<AttendanceRecord, DatabaseError> {
+        let statement = "select id, workplace_id, user_id, event, recorded_at, deleted_at from attendance_records where id = $1 and workplace_id = $2 and ($3 or user_id = $4) and deleted_at is not null";
+        let attendance_record: AttendanceRecord = sqlx::query_as(statement)
             .bind(id)
             .bind(workplace.id)
-            .execute(self.executor)
+            .bind(workplace.viewer.role.manages_attendance())
+            .bind(workplace.viewer.user_id)
+            .fetch_one(self.executor)
             .await
-            .inspect_err(|e| log::error!("Failed to delete attendance_record: {:?}", e))?;
+            .inspect_err(|e| log::error!("Failed to find deleted attendance_record: {:?}", e))?;
 
-        Ok(())
+        Ok(attendance_record)
+    }
+
+    async fn list_deleted(
+        &self,
+        workplace: &Workplace,
+    ) -> Result<Vec<AttendanceRecord>, DatabaseError> {
+        let statement = "select id, workplace_id, user_id, event, recorded_at, deleted_at from attendance_records where workplace_id = $1 and ($2 or user_id = $3) and deleted_at is not null order by deleted_at desc";
+        let attendance_records: Vec<AttendanceRecord> = sqlx::query_as(statement)
+            .bind(workplace.id)
+            .bind(workplace.viewer.role.manages_attendance())
+            .bind(workplace.viewer.user_id)
+            .fetch_all(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to query deleted attendance_records: {:?}", e))?;
+        Ok(attendance_records)
+    }
+
+    async fn purge_deleted(&self, deleted_before: &Timestamp) -> Result<u64, DatabaseError> {
+        let statement =
+            "delete from attendance_records where deleted_at is not null and deleted_at < $1";
+        let result = sqlx::query(statement)
+            .bind(deleted_before)
+            .execute(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to purge attendance_records: {:?}", e))?;
+        Ok(result.rows_affected())
     }
 
     async fn list(
@@ -76,13 +177,225 @@ where
         start_time: &Timestamp,
         end_time: &Timestamp,
     ) -> Result<Vec<AttendanceRecord>, DatabaseError> {
-        let statement = "select id, workplace_id, event, recorded_at from attendance_records where workplace_id = $1 and recorded_at >= $2 and recorded_at < $3 order by recorded_at";
+        let statement = "select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = $1 and ($2 or user_id = $3) and recorded_at >= $4 and recorded_at < $5 and deleted_at is null order by recorded_at";
         let attendance_records: Vec<AttendanceRecord> = sqlx::query_as(statement)
             .bind(workplace.id)
+            .bind(workplace.viewer.role.manages_attendance())
+            .bind(workplace.viewer.user_id)
             .bind(start_time)
             .bind(end_time)
             .fetch_all(self.executor)
             .await?;
         Ok(attendance_records)
     }
+
+    async fn find_previous(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        datetime: &Timestamp,
+        excluding: Option<AttendanceRecordId>,
+    ) -> Result<Option<AttendanceRecord>, DatabaseError> {
+        find_previous(self.executor, workplace, user_id, datetime, excluding).await
+    }
+
+    async fn find_next(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        datetime: &Timestamp,
+        excluding: Option<AttendanceRecordId>,
+    ) -> Result<Option<AttendanceRecord>, DatabaseError> {
+        find_next(self.executor, workplace, user_id, datetime, excluding).await
+    }
+}
+
+// The statements below take any executor, so that `AuditedAttendanceRecordRepository` can run
+// them in the same transaction as the audit trail.
+
+pub(super) async fn insert<'e, E>(
+    executor: E,
+    workplace: &Workplace,
+    event: &Event,
+    datetime: &Timestamp,
+) -> Result<AttendanceRecord, DatabaseError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let statement = "insert into attendance_records (workplace_id, user_id, event, recorded_at, created_at) values ($1, $2, $3, $4, $5) returning id, workplace_id, user_id, event, recorded_at";
+    let now = Utc::now();
+    let attendance_record = sqlx::query_as(statement)
+        .bind(workplace.id)
+        .bind(workplace.viewer.user_id)
+        .bind(event)
+        .bind(datetime)
+        .bind(now)
+        .fetch_one(executor)
+        .await
+        .inspect_err(|e| log::error!("Failed to create attendance_record: {:?}", e))?;
+
+    Ok(attendance_record)
+}
+
+pub(super) async fn find<'e, E>(
+    executor: E,
+    workplace: &Workplace,
+    id: AttendanceRecordId,
+) -> Result<AttendanceRecord, DatabaseError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let statement = "select id, workplace_id, user_id, event, recorded_at from attendance_records where id = $1 and workplace_id = $2 and ($3 or user_id = $4) and deleted_at is null";
+    let attendance_record: AttendanceRecord = sqlx::query_as(statement)
+        .bind(id)
+        .bind(workplace.id)
+        .bind(workplace.viewer.role.manages_attendance())
+        .bind(workplace.viewer.user_id)
+        .fetch_one(executor)
+        .await
+        .inspect_err(|e| log::error!("Failed to find attendance_record: {:?}", e))?;
+
+    Ok(attendance_record)
+}
+
+pub(super) async fn update<'e, E>(
+    executor: E,
+    workplace: &Workplace,
+    attendance_record: &AttendanceRecord,
+) -> Result<AttendanceRecord, DatabaseError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let statement = "update attendance_records set event = $1, recorded_at = $2 where id = $3 and workplace_id = $4 and ($5 or user_id = $6) and deleted_at is null returning id, workplace_id, user_id, event, recorded_at";
+    let attendance_record: AttendanceRecord = sqlx::query_as(statement)
+        .bind(&attendance_record.event)
+        .bind(&attendance_record.recorded_at)
+        .bind(attendance_record.id)
+        .bind(workplace.id)
+        .bind(workplace.viewer.role.manages_attendance())
+        .bind(workplace.viewer.user_id)
+        .fetch_one(executor)
+        .await
+        .inspect_err(|e| log::error!("Failed to update attendance_record: {:?}", e))?;
+
+    Ok(attendance_record)
+}
+
+pub(super) async fn soft_delete<'e, E>(
+    executor: E,
+    workplace: &Workplace,
+    id: AttendanceRecordId,
+) -> Result<(), DatabaseError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let statement = "update attendance_records set deleted_at = $1 where id = $2 and workplace_id = $3 and ($4 or user_id = $5) and deleted_at is null";
+    let now = Utc::now();
+    sqlx::query(statement)
+        .bind(now)
+        .bind(id)
+        .bind(workplace.id)
+        .bind(workplace.viewer.role.manages_attendance())
+        .bind(workplace.viewer.user_id)
+        .execute(executor)
+        .await
+        .inspect_err(|e| log::error!("Failed to delete attendance_record: {:?}", e))?;
+
+    Ok(())
+}
+
+pub(super) async fn restore<'e, E>(
+    executor: E,
+    workplace: &Workplace,
+    id: AttendanceRecordId,
+) -> Result<AttendanceRecord, DatabaseError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let statement = "update attendance_records set deleted_at = null where id = $1 and workplace_id = $2 and ($3 or user_id = $4) and deleted_at is not null returning id, workplace_id, user_id, event, recorded_at";
+    let attendance_record: AttendanceRecord = sqlx::query_as(statement)
+        .bind(id)
+        .bind(workplace.id)
+        .bind(workplace.viewer.role.manages_attendance())
+        .bind(workplace.viewer.user_id)
+        .fetch_one(executor)
+        .await
+        .inspect_err(|e| log::error!("Failed to restore attendance_record: {:?}", e))?;
+
+    Ok(attendance_record)
+}
+
+pub(super) async fn find_previous<'e, E>(
+    executor: E,
+    workplace: &Workplace,
+    user_id: UserId,
+    datetime: &Timestamp,
+    excluding: Option<AttendanceRecordId>,
+) -> Result<Option<AttendanceRecord>, DatabaseError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let statement = "select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = $1 and ($2 or user_id = $3) and user_id = $4 and recorded_at <= $5 and ($6 is null or id <> $6) and deleted_at is null order by recorded_at desc, id desc limit 1";
+    let attendance_record: Option<AttendanceRecord> = sqlx::query_as(statement)
+        .bind(workplace.id)
+        .bind(workplace.viewer.role.manages_attendance())
+        .bind(workplace.viewer.user_id)
+        .bind(user_id)
+        .bind(datetime)
+        .bind(excluding)
+        .fetch_optional(executor)
+        .await
+        .inspect_err(|e| log::error!("Failed to find previous attendance_record: {:?}", e))?;
+    Ok(attendance_record)
+}
+
+pub(super) async fn find_next<'e, E>(
+    executor: E,
+    workplace: &Workplace,
+    user_id: UserId,
+    datetime: &Timestamp,
+    excluding: Option<AttendanceRecordId>,
+) -> Result<Option<AttendanceRecord>, DatabaseError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let statement = "select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = $1 and ($2 or user_id = $3) and user_id = $4 and recorded_at > $5 and ($6 is null or id <> $6) and deleted_at is null order by recorded_at, id limit 1";
+    let attendance_record: Option<AttendanceRecord> = sqlx::query_as(statement)
+        .bind(workplace.id)
+        .bind(workplace.viewer.role.manages_attendance())
+        .bind(workplace.viewer.user_id)
+        .bind(user_id)
+        .bind(datetime)
+        .bind(excluding)
+        .fetch_optional(executor)
+        .await
+        .inspect_err(|e| log::error!("Failed to find next attendance_record: {:?}", e))?;
+    Ok(attendance_record)
+}
+
+/// Takes the write lock before reading, as a deferred transaction would let two concurrent
+/// registrations both read the same placement and insert after it.
+pub(super) const BEGIN_IMMEDIATE: &str = "begin immediate";
+
+/// Inserts a record for the viewer of `workplace` once its placement accepts `event`. It is meant
+/// to run in a transaction begun with `BEGIN_IMMEDIATE`.
+pub(super) async fn insert_in_sequence(
+    conn: &mut SqliteConnection,
+    workplace: &Workplace,
+    event: &Event,
+    datetime: &Timestamp,
+) -> Result<Result<AttendanceRecord, PlacementError>, DatabaseError> {
+    let user_id = workplace.viewer.user_id;
+    let locking = timesheet::find_locking(&mut *conn, workplace, user_id, datetime).await?;
+    let placement = Placement {
+        locked: locking.is_some(),
+        previous: find_previous(&mut *conn, workplace, user_id, datetime, None).await?,
+        next: find_next(&mut *conn, workplace, user_id, datetime, None).await?,
+    };
+    if let Err(error) = placement.check(event) {
+        return Ok(Err(error));
+    }
+
+    let attendance_record = insert(&mut *conn, workplace, event, datetime).await?;
+    Ok(Ok(attendance_record))
 }
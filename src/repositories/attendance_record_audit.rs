@@ -0,0 +1,101 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{Executor, Sqlite};
+
+use crate::{
+    errors::DatabaseError,
+    models::{
+        Actor, AttendanceRecord, AttendanceRecordAudit, AttendanceRecordId, AuditAction, Workplace,
+    },
+    repositories::AttendanceRecordAuditRepository,
+};
+
+pub struct RdbAttendanceRecordAuditRepository<'a, T: Executor<'a>> {
+    executor: T,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> RdbAttendanceRecordAuditRepository<'a, T>
+where
+    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+{
+    pub fn new(executor: T) -> Self {
+        Self {
+            executor,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<'a, T> AttendanceRecordAuditRepository for RdbAttendanceRecordAuditRepository<'a, T>
+where
+    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+{
+    async fn create(
+        &self,
+        actor: &Actor,
+        action: AuditAction,
+        previous: Option<&AttendanceRecord>,
+        current: Option<&AttendanceRecord>,
+    ) -> Result<(), DatabaseError> {
+        insert(self.executor, actor, action, previous, current).await
+    }
+
+    async fn list(
+        &self,
+        workplace: &Workplace,
+        attendance_record_id: AttendanceRecordId,
+    ) -> Result<Vec<AttendanceRecordAudit>, DatabaseError> {
+        let statement = "select id, attendance_record_id, workplace_id, user_id, action, channel, api_key_id, previous_event, previous_recorded_at, event, recorded_at, created_at from attendance_record_audits where workplace_id = $1 and attendance_record_id = $2 and ($3 or exists (select 1 from attendance_records where attendance_records.id = attendance_record_audits.attendance_record_id and attendance_records.user_id = $4)) order by created_at, id";
+        let audits: Vec<AttendanceRecordAudit> = sqlx::query_as(statement)
+            .bind(workplace.id)
+            .bind(attendance_record_id)
+            .bind(workplace.viewer.role.manages_attendance())
+            .bind(workplace.viewer.user_id)
+            .fetch_all(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to query attendance_record_audits: {:?}", e))?;
+
+        Ok(audits)
+    }
+}
+
+/// Takes any executor, so that `AuditedAttendanceRecordRepository` can write the audit trail in
+/// the same transaction as the change itself.
+pub(super) async fn insert<'e, E>(
+    executor: E,
+    actor: &Actor,
+    action: AuditAction,
+    previous: Option<&AttendanceRecord>,
+    current: Option<&AttendanceRecord>,
+) -> Result<(), DatabaseError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let Some(attendance_record) = current.or(previous) else {
+        return Ok(());
+    };
+
+    let statement = "insert into attendance_record_audits (attendance_record_id, workplace_id, user_id, action, channel, api_key_id, previous_event, previous_recorded_at, event, recorded_at, created_at) values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)";
+    let now = Utc::now();
+    sqlx::query(statement)
+        .bind(attendance_record.id)
+        .bind(attendance_record.workplace_id)
+        .bind(actor.user_id)
+        .bind(action)
+        .bind(actor.channel)
+        .bind(actor.api_key_id)
+        .bind(previous.map(|record| &record.event))
+        .bind(previous.map(|record| &record.recorded_at))
+        .bind(current.map(|record| &record.event))
+        .bind(current.map(|record| &record.recorded_at))
+        .bind(now)
+        .execute(executor)
+        .await
+        .inspect_err(|e| log::error!("Failed to create attendance_record_audit: {:?}", e))?;
+
+    Ok(())
+}
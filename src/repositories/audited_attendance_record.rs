@@ -0,0 +1,247 @@
+use async_trait::async_trait;
+use sqlx::{Pool, Sqlite, Transaction};
+
+use super::{
+    AttendanceRecordRepository, attendance_record,
+    attendance_record::RdbAttendanceRecordRepository, attendance_record_audit,
+};
+use crate::{
+    errors::DatabaseError,
+    models::{
+        Actor, AttendanceRecord, AttendanceRecordId, AuditAction, Timestamp, UserId, Workplace,
+        attendance_record::{Event, PlacementError},
+    },
+};
+
+/// Wraps the attendance record repository so that every change is written to the audit trail
+/// on behalf of `actor`, in the same transaction as the change itself.
+pub struct AuditedAttendanceRecordRepository<'a> {
+    pool: &'a Pool<Sqlite>,
+    attendance_records: RdbAttendanceRecordRepository<'a, &'a Pool<Sqlite>>,
+    actor: &'a Actor,
+}
+
+impl<'a> AuditedAttendanceRecordRepository<'a> {
+    pub fn new(pool: &'a Pool<Sqlite>, actor: &'a Actor) -> Self {
+        Self {
+            pool,
+            attendance_records: RdbAttendanceRecordRepository::new(pool),
+            actor,
+        }
+    }
+
+    async fn begin(&self) -> Result<Transaction<'static, Sqlite>, DatabaseError> {
+        let tx = self
+            .pool
+            .begin()
+            .await
+            .inspect_err(|e| log::error!("Failed to begin transaction: {:?}", e))?;
+        Ok(tx)
+    }
+}
+
+async fn commit(tx: Transaction<'static, Sqlite>) -> Result<(), DatabaseError> {
+    tx.commit()
+        .await
+        .inspect_err(|e| log::error!("Failed to commit transaction: {:?}", e))?;
+    Ok(())
+}
+
+#[async_trait]
+impl AttendanceRecordRepository for AuditedAttendanceRecordRepository<'_> {
+    async fn create(
+        &self,
+        workplace: &Workplace,
+        event: &Event,
+        datetime: &Timestamp,
+    ) -> Result<AttendanceRecord, DatabaseError> {
+        let mut tx = self.begin().await?;
+        let attendance_record =
+            attendance_record::insert(&mut *tx, workplace, event, datetime).await?;
+        attendance_record_audit::insert(
+            &mut *tx,
+            self.actor,
+            AuditAction::Create,
+            None,
+            Some(&attendance_record),
+        )
+        .await?;
+        commit(tx).await?;
+        Ok(attendance_record)
+    }
+
+    async fn create_in_sequence(
+        &self,
+        workplace: &Workplace,
+        event: &Event,
+        datetime: &Timestamp,
+    ) -> Result<Result<AttendanceRecord, PlacementError>, DatabaseError> {
+        let mut tx = self
+            .pool
+            .begin_with(attendance_record::BEGIN_IMMEDIATE)
+            .await
+            .inspect_err(|e| log::error!("Failed to begin transaction: {:?}", e))?;
+        let attendance_record =
+            match attendance_record::insert_in_sequence(&mut tx, workplace, event, datetime).await?
+            {
+                Ok(attendance_record) => attendance_record,
+                Err(error) => return Ok(Err(error)),
+            };
+        attendance_record_audit::insert(
+            &mut *tx,
+            self.actor,
+            AuditAction::Create,
+            None,
+            Some(&attendance_record),
+        )
+        .await?;
+        commit(tx).await?;
+        Ok(Ok(attendance_record))
+    }
+
+    async fn create_many(
+        &self,
+        workplace: &Workplace,
+        entries: &[(Event, Timestamp)],
+    ) -> Result<Vec<AttendanceRecord>, DatabaseError> {
+        let mut tx = self.begin().await?;
+        let mut attendance_records = Vec::with_capacity(entries.len());
+        for (event, datetime) in entries {
+            let attendance_record =
+                attendance_record::insert(&mut *tx, workplace, event, datetime).await?;
+            attendance_record_audit::insert(
+                &mut *tx,
+                self.actor,
+                AuditAction::Create,
+                None,
+                Some(&attendance_record),
+            )
+            .await?;
+            attendance_records.push(attendance_record);
+        }
+        commit(tx).await?;
+        Ok(attendance_records)
+    }
+
+    async fn find(
+        &self,
+        workplace: &Workplace,
+        id: AttendanceRecordId,
+    ) -> Result<AttendanceRecord, DatabaseError> {
+        self.attendance_records.find(workplace, id).await
+    }
+
+    async fn update(
+        &self,
+        workplace: &Workplace,
+        attendance_record: &AttendanceRecord,
+    ) -> Result<AttendanceRecord, DatabaseError> {
+        let mut tx = self.begin().await?;
+        let previous = attendance_record::find(&mut *tx, workplace, attendance_record.id).await?;
+        let updated = attendance_record::update(&mut *tx, workplace, attendance_record).await?;
+        attendance_record_audit::insert(
+            &mut *tx,
+            self.actor,
+            AuditAction::Update,
+            Some(&previous),
+            Some(&updated),
+        )
+        .await?;
+        commit(tx).await?;
+        Ok(updated)
+    }
+
+    async fn destroy(
+        &self,
+        workplace: &Workplace,
+        id: AttendanceRecordId,
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.begin().await?;
+        let previous = attendance_record::find(&mut *tx, workplace, id).await?;
+        attendance_record::soft_delete(&mut *tx, workplace, id).await?;
+        attendance_record_audit::insert(
+            &mut *tx,
+            self.actor,
+            AuditAction::Destroy,
+            Some(&previous),
+            None,
+        )
+        .await?;
+        commit(tx).await
+    }
+
+    async fn restore(
+        &self,
+        workplace: &Workplace,
+        id: AttendanceRecordId,
+    ) -> Result<AttendanceRecord, DatabaseError> {
+        let mut tx = self.begin().await?;
+        let attendance_record = attendance_record::restore(&mut *tx, workplace, id).await?;
+        attendance_record_audit::insert(
+            &mut *tx,
+            self.actor,
+            AuditAction::Restore,
+            None,
+            Some(&attendance_record),
+        )
+        .await?;
+        commit(tx).await?;
+        Ok(attendance_record)
+    }
+
+    async fn find_deleted(
+        &self,
+        workplace: &Workplace,
+        id: AttendanceRecordId,
+    ) -> Result<AttendanceRecord, DatabaseError> {
+        self.attendance_records.find_deleted(workplace, id).await
+    }
+
+    async fn list_deleted(
+        &self,
+        workplace: &Workplace,
+    ) -> Result<Vec<AttendanceRecord>, DatabaseError> {
+        self.attendance_records.list_deleted(workplace).await
+    }
+
+    /// Purging is not audited: it runs without an actor as part of the trash retention, only
+    /// removes records whose deletion is already in the trail, and the trail itself is kept.
+    async fn purge_deleted(&self, deleted_before: &Timestamp) -> Result<u64, DatabaseError> {
+        self.attendance_records.purge_deleted(deleted_before).await
+    }
+
+    async fn list(
+        &self,
+        workplace: &Workplace,
+        start_time: &Timestamp,
+        end_time: &Timestamp,
+    ) -> Result<Vec<AttendanceRecord>, DatabaseError> {
+        self.attendance_records
+            .list(workplace, start_time, end_time)
+            .await
+    }
+
+    async fn find_previous(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        datetime: &Timestamp,
+        excluding: Option<AttendanceRecordId>,
+    ) -> Result<Option<AttendanceRecord>, DatabaseError> {
+        self.attendance_records
+            .find_previous(workplace, user_id, datetime, excluding)
+            .await
+    }
+
+    async fn find_next(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        datetime: &Timestamp,
+        excluding: Option<AttendanceRecordId>,
+    ) -> Result<Option<AttendanceRecord>, DatabaseError> {
+        self.attendance_records
+            .find_next(workplace, user_id, datetime, excluding)
+            .await
+    }
+}
@@ -0,0 +1,87 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{Executor, Sqlite};
+
+use crate::{
+    errors::DatabaseError,
+    models::{CalendarFeed, Workplace},
+    repositories::CalendarFeedRepository,
+};
+
+pub struct RdbCalendarFeedRepository<'a, T: Executor<'a>> {
+    executor: T,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> RdbCalendarFeedRepository<'a, T>
+where
+    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+{
+    pub fn new(executor: T) -> Self {
+        Self {
+            executor,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<'a, T> CalendarFeedRepository for RdbCalendarFeedRepository<'a, T>
+where
+    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+{
+    async fn find(&self, workplace: &Workplace) -> Result<Option<CalendarFeed>, DatabaseError> {
+        let statement = "select id, workplace_id, user_id, digest, created_at from calendar_feeds where workplace_id = $1 and user_id = $2";
+        let calendar_feed: Option<CalendarFeed> = sqlx::query_as(statement)
+            .bind(workplace.id)
+            .bind(workplace.viewer.user_id)
+            .fetch_optional(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to find calendar_feed: {:?}", e))?;
+        Ok(calendar_feed)
+    }
+
+    async fn find_by_digest(&self, digest: &str) -> Result<Option<CalendarFeed>, DatabaseError> {
+        let statement = "select id, workplace_id, user_id, digest, created_at from calendar_feeds where digest = $1";
+        let calendar_feed: Option<CalendarFeed> = sqlx::query_as(statement)
+            .bind(digest)
+            .fetch_optional(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to query calendar_feeds: {:?}", e))?;
+        Ok(calendar_feed)
+    }
+
+    async fn create(
+        &self,
+        workplace: &Workplace,
+        digest: &str,
+    ) -> Result<CalendarFeed, DatabaseError> {
+        let statement = "insert into calendar_feeds (workplace_id, user_id, digest, created_at) values ($1, $2, $3, $4) on conflict (workplace_id, user_id) do update set digest = excluded.digest, created_at = excluded.created_at returning id, workplace_id, user_id, digest, created_at";
+        let now = Utc::now();
+        let calendar_feed: CalendarFeed = sqlx::query_as(statement)
+            .bind(workplace.id)
+            .bind(workplace.viewer.user_id)
+            .bind(digest)
+            .bind(now)
+            .fetch_one(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to create calendar_feed: {:?}", e))?;
+        Ok(calendar_feed)
+    }
+
+    async fn destroy(&self, workplace: &Workplace) -> Result<(), DatabaseError> {
+        let result =
+            sqlx::query("delete from calendar_feeds where workplace_id = $1 and user_id = $2")
+                .bind(workplace.id)
+                .bind(workplace.viewer.user_id)
+                .execute(self.executor)
+                .await
+                .inspect_err(|e| log::error!("Failed to delete calendar_feed: {:?}", e))?;
+        if result.rows_affected() == 0 {
+            return Err(DatabaseError::RecordNotFound);
+        }
+        Ok(())
+    }
+}
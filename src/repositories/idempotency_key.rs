@@ -0,0 +1,98 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use sqlx::{Executor, Sqlite};
+
+use super::IdempotencyKeyRepository;
+use crate::{
+    errors::DatabaseError,
+    models::{ApiKeyId, IdempotencyKey, Timestamp},
+};
+
+pub struct RdbIdempotencyKeyRepository<'a, T: Executor<'a>> {
+    executor: T,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> RdbIdempotencyKeyRepository<'a, T>
+where
+    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+{
+    pub fn new(executor: T) -> Self {
+        Self {
+            executor,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<'a, T> IdempotencyKeyRepository for RdbIdempotencyKeyRepository<'a, T>
+where
+    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+{
+    async fn find(
+        &self,
+        api_key_id: ApiKeyId,
+        key: &str,
+    ) -> Result<Option<IdempotencyKey>, DatabaseError> {
+        let statement = "select api_key_id, user_id, key, request_uri, response_status, response_body, created_at, completed_at from idempotency_keys where api_key_id = $1 and key = $2";
+        let idempotency_key: Option<IdempotencyKey> = sqlx::query_as(statement)
+            .bind(api_key_id)
+            .bind(key)
+            .fetch_optional(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to query idempotency_keys: {:?}", e))?;
+
+        Ok(idempotency_key)
+    }
+
+    async fn reserve(&self, idempotency_key: &IdempotencyKey) -> Result<bool, DatabaseError> {
+        let statement = "insert into idempotency_keys (api_key_id, user_id, key, request_uri, created_at) values ($1, $2, $3, $4, $5) on conflict (api_key_id, key) do nothing";
+        let result = sqlx::query(statement)
+            .bind(idempotency_key.api_key_id)
+            .bind(idempotency_key.user_id)
+            .bind(&idempotency_key.key)
+            .bind(&idempotency_key.request_uri)
+            .bind(&idempotency_key.created_at)
+            .execute(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to reserve idempotency_key: {:?}", e))?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn complete(&self, idempotency_key: &IdempotencyKey) -> Result<(), DatabaseError> {
+        let statement = "update idempotency_keys set response_status = $1, response_body = $2, completed_at = $3 where api_key_id = $4 and key = $5 and completed_at is null";
+        sqlx::query(statement)
+            .bind(idempotency_key.response_status)
+            .bind(&idempotency_key.response_body)
+            .bind(&idempotency_key.completed_at)
+            .bind(idempotency_key.api_key_id)
+            .bind(&idempotency_key.key)
+            .execute(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to complete idempotency_key: {:?}", e))?;
+        Ok(())
+    }
+
+    async fn release(&self, idempotency_key: &IdempotencyKey) -> Result<(), DatabaseError> {
+        let statement = "delete from idempotency_keys where api_key_id = $1 and key = $2 and completed_at is null";
+        sqlx::query(statement)
+            .bind(idempotency_key.api_key_id)
+            .bind(&idempotency_key.key)
+            .execute(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to release idempotency_key: {:?}", e))?;
+        Ok(())
+    }
+
+    async fn purge_expired(&self, created_before: &Timestamp) -> Result<u64, DatabaseError> {
+        let statement = "delete from idempotency_keys where created_at < $1";
+        let result = sqlx::query(statement)
+            .bind(created_before)
+            .execute(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to purge idempotency_keys: {:?}", e))?;
+        Ok(result.rows_affected())
+    }
+}
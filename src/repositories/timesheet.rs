@@ -0,0 +1,133 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{Executor, Sqlite};
+
+use crate::{
+    errors::DatabaseError,
+    models::{Timesheet, Timestamp, UserId, Workplace},
+    repositories::TimesheetRepository,
+};
+
+pub struct RdbTimesheetRepository<'a, T: Executor<'a>> {
+    executor: T,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> RdbTimesheetRepository<'a, T>
+where
+    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+{
+    pub fn new(executor: T) -> Self {
+        Self {
+            executor,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<'a, T> TimesheetRepository for RdbTimesheetRepository<'a, T>
+where
+    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+{
+    async fn list(
+        &self,
+        workplace: &Workplace,
+        year: Option<i32>,
+    ) -> Result<Vec<Timesheet>, DatabaseError> {
+        let statement = "select workplace_id, user_id, year, month, state, comment, starts_at, ends_at, submitted_at, reviewed_by, reviewed_at from timesheets where workplace_id = $1 and ($2 or user_id = $3) and ($4 is null or year = $4) order by year, month, user_id";
+        let timesheets: Vec<Timesheet> = sqlx::query_as(statement)
+            .bind(workplace.id)
+            .bind(workplace.viewer.role.manages_attendance())
+            .bind(workplace.viewer.user_id)
+            .bind(year)
+            .fetch_all(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to query timesheets: {:?}", e))?;
+        Ok(timesheets)
+    }
+
+    async fn find(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        year: i32,
+        month: u32,
+    ) -> Result<Option<Timesheet>, DatabaseError> {
+        let statement = "select workplace_id, user_id, year, month, state, comment, starts_at, ends_at, submitted_at, reviewed_by, reviewed_at from timesheets where workplace_id = $1 and user_id = $2 and year = $3 and month = $4 and ($5 or user_id = $6)";
+        let timesheet: Option<Timesheet> = sqlx::query_as(statement)
+            .bind(workplace.id)
+            .bind(user_id)
+            .bind(year)
+            .bind(month)
+            .bind(workplace.viewer.role.manages_attendance())
+            .bind(workplace.viewer.user_id)
+            .fetch_optional(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to find timesheet: {:?}", e))?;
+        Ok(timesheet)
+    }
+
+    async fn find_locking(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        datetime: &Timestamp,
+    ) -> Result<Option<Timesheet>, DatabaseError> {
+        find_locking(self.executor, workplace, user_id, datetime).await
+    }
+
+    async fn save(
+        &self,
+        workplace: &Workplace,
+        timesheet: &Timesheet,
+    ) -> Result<Timesheet, DatabaseError> {
+        let statement = "insert into timesheets (workplace_id, user_id, year, month, state, comment, starts_at, ends_at, submitted_at, reviewed_by, reviewed_at, created_at, updated_at) select $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $12 where $13 or $2 = $14 on conflict (workplace_id, user_id, year, month) do update set state = excluded.state, comment = excluded.comment, submitted_at = excluded.submitted_at, reviewed_by = excluded.reviewed_by, reviewed_at = excluded.reviewed_at, updated_at = excluded.updated_at returning workplace_id, user_id, year, month, state, comment, starts_at, ends_at, submitted_at, reviewed_by, reviewed_at";
+        let now = Utc::now();
+        let timesheet: Timesheet = sqlx::query_as(statement)
+            .bind(workplace.id)
+            .bind(timesheet.user_id)
+            .bind(timesheet.year)
+            .bind(timesheet.month)
+            .bind(timesheet.state)
+            .bind(&timesheet.comment)
+            .bind(&timesheet.starts_at)
+            .bind(&timesheet.ends_at)
+            .bind(&timesheet.submitted_at)
+            .bind(timesheet.reviewed_by)
+            .bind(&timesheet.reviewed_at)
+            .bind(now)
+            .bind(workplace.viewer.role.manages_attendance())
+            .bind(workplace.viewer.user_id)
+            .fetch_one(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to save timesheet: {:?}", e))?;
+        Ok(timesheet)
+    }
+}
+
+// Attendance registration reads the lock in the same transaction as the record it inserts.
+
+pub(super) async fn find_locking<'e, E>(
+    executor: E,
+    workplace: &Workplace,
+    user_id: UserId,
+    datetime: &Timestamp,
+) -> Result<Option<Timesheet>, DatabaseError>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let statement = "select workplace_id, user_id, year, month, state, comment, starts_at, ends_at, submitted_at, reviewed_by, reviewed_at from timesheets where workplace_id = $1 and user_id = $2 and state = 'approved' and starts_at <= $3 and ends_at > $3 and ($4 or user_id = $5) limit 1";
+    let timesheet: Option<Timesheet> = sqlx::query_as(statement)
+        .bind(workplace.id)
+        .bind(user_id)
+        .bind(datetime)
+        .bind(workplace.viewer.role.manages_attendance())
+        .bind(workplace.viewer.user_id)
+        .fetch_optional(executor)
+        .await
+        .inspect_err(|e| log::error!("Failed to find locking timesheet: {:?}", e))?;
+    Ok(timesheet)
+}
@@ -2,11 +2,11 @@ use std::marker::PhantomData;
 
 use async_trait::async_trait;
 use chrono::Utc;
-use sqlx::{Executor, Sqlite};
+use sqlx::{Acquire, Executor, Sqlite};
 
 use crate::{
     errors::DatabaseError,
-    models::{User, Workplace, WorkplaceId},
+    models::{BreakRules, Timezone, User, Workplace, WorkplaceId},
     repositories::WorkplaceRepository,
 };
 
@@ -30,13 +30,18 @@ where
 #[async_trait]
 impl<'a, T> WorkplaceRepository for RdbWorkplaceRepository<'a, T>
 where
-    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+    T: Executor<'a, Database = Sqlite> + Acquire<'a, Database = Sqlite> + Copy + Sync,
 {
-    async fn list(&self, user: &User) -> Result<Vec<Workplace>, DatabaseError> {
+    async fn list(
+        &self,
+        user: &User,
+        including_archived: bool,
+    ) -> Result<Vec<Workplace>, DatabaseError> {
         let workplaces: Vec<Workplace> = sqlx::query_as(
-            "select id, user_id, name from workplaces where user_id = $1 order by id",
+            "select workplaces.id, workplaces.user_id, name, timezone, break_rules, daily_overtime_threshold_minutes, weekly_overtime_threshold_minutes, rest_days, closing_day, archived_at, $1 as viewer_id, case when workplaces.user_id = $1 then 'owner' else workplace_memberships.role end as viewer_role from workplaces left join workplace_memberships on workplace_memberships.workplace_id = workplaces.id and workplace_memberships.user_id = $1 where (workplaces.user_id = $1 or workplace_memberships.id is not null) and ($2 or archived_at is null) order by workplaces.id",
         )
         .bind(user.id)
+        .bind(including_archived)
         .fetch_all(self.executor)
         .await
         .inspect_err(|e| log::error!("Failed to query workplaces: {:?}", e))?;
@@ -44,13 +49,21 @@ where
         Ok(workplaces)
     }
 
-    async fn create(&self, user: &User, name: &str) -> Result<Workplace, DatabaseError> {
-        let statement = "insert into workplaces (user_id, name, created_at, updated_at) values ($1, $2, $3, $4) returning id, user_id, name";
+    async fn create(
+        &self,
+        user: &User,
+        name: &str,
+        timezone: &Timezone,
+        break_rules: &BreakRules,
+    ) -> Result<Workplace, DatabaseError> {
+        let statement = "insert into workplaces (user_id, name, timezone, break_rules, created_at, updated_at) values ($1, $2, $3, $4, $5, $6) returning id, user_id, name, timezone, break_rules, daily_overtime_threshold_minutes, weekly_overtime_threshold_minutes, rest_days, closing_day, archived_at, user_id as viewer_id, 'owner' as viewer_role";
         let now = Utc::now();
 
         let workplace: Workplace = sqlx::query_as(statement)
             .bind(user.id)
             .bind(name)
+            .bind(timezone.name())
+            .bind(break_rules.to_string())
             .bind(now)
             .bind(now)
             .fetch_one(self.executor)
@@ -61,7 +74,7 @@ where
     }
 
     async fn find(&self, user: &User, id: WorkplaceId) -> Result<Workplace, DatabaseError> {
-        let statement = "select id, user_id, name from workplaces where user_id = $1 and id = $2";
+        let statement = "select workplaces.id, workplaces.user_id, name, timezone, break_rules, daily_overtime_threshold_minutes, weekly_overtime_threshold_minutes, rest_days, closing_day, archived_at, $1 as viewer_id, case when workplaces.user_id = $1 then 'owner' else workplace_memberships.role end as viewer_role from workplaces left join workplace_memberships on workplace_memberships.workplace_id = workplaces.id and workplace_memberships.user_id = $1 where (workplaces.user_id = $1 or workplace_memberships.id is not null) and workplaces.id = $2";
         let workplace: Workplace = sqlx::query_as(statement)
             .bind(user.id)
             .bind(id)
@@ -71,4 +84,96 @@ where
 
         Ok(workplace)
     }
+
+    async fn update(&self, workplace: &Workplace) -> Result<Workplace, DatabaseError> {
+        let statement = "update workplaces set name = $1, timezone = $2, break_rules = $3, daily_overtime_threshold_minutes = $4, weekly_overtime_threshold_minutes = $5, rest_days = $6, closing_day = $7, archived_at = $8, updated_at = $9 where id = $10 and user_id = $11 returning id, user_id, name, timezone, break_rules, daily_overtime_threshold_minutes, weekly_overtime_threshold_minutes, rest_days, closing_day, archived_at, user_id as viewer_id, 'owner' as viewer_role";
+        let now = Utc::now();
+
+        let workplace: Workplace = sqlx::query_as(statement)
+            .bind(&workplace.name)
+            .bind(workplace.timezone.name())
+            .bind(workplace.break_rules.to_string())
+            .bind(workplace.overtime_rules.daily_threshold_minutes)
+            .bind(workplace.overtime_rules.weekly_threshold_minutes)
+            .bind(String::from(workplace.overtime_rules.rest_days.clone()))
+            .bind(workplace.closing_day)
+            .bind(&workplace.archived_at)
+            .bind(now)
+            .bind(workplace.id)
+            .bind(workplace.viewer.user_id)
+            .fetch_one(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to update workplace: {:?}", e))?;
+
+        Ok(workplace)
+    }
+
+    async fn delete_cascading(
+        &self,
+        workplace: &Workplace,
+        including_attendance_records: bool,
+    ) -> Result<bool, DatabaseError> {
+        let mut tx = self
+            .executor
+            .begin()
+            .await
+            .inspect_err(|e| log::error!("Failed to begin transaction: {:?}", e))?;
+
+        // Only the owner may delete the workplace, and nothing else goes unless it does.
+        let result = sqlx::query("delete from workplaces where id = $1 and user_id = $2")
+            .bind(workplace.id)
+            .bind(workplace.viewer.user_id)
+            .execute(&mut *tx)
+            .await
+            .inspect_err(|e| log::error!("Failed to delete workplace: {:?}", e))?;
+        if result.rows_affected() != 1 {
+            return Err(DatabaseError::RecordNotFound);
+        }
+
+        if including_attendance_records {
+            sqlx::query("delete from attendance_record_audits where workplace_id = $1")
+                .bind(workplace.id)
+                .execute(&mut *tx)
+                .await
+                .inspect_err(|e| {
+                    log::error!("Failed to delete attendance_record_audits: {:?}", e)
+                })?;
+            sqlx::query("delete from attendance_records where workplace_id = $1")
+                .bind(workplace.id)
+                .execute(&mut *tx)
+                .await
+                .inspect_err(|e| log::error!("Failed to delete attendance_records: {:?}", e))?;
+        } else {
+            let exists: bool = sqlx::query_scalar(
+                "select exists (select 1 from attendance_records where workplace_id = $1)",
+            )
+            .bind(workplace.id)
+            .fetch_one(&mut *tx)
+            .await
+            .inspect_err(|e| log::error!("Failed to query attendance_records: {:?}", e))?;
+            if exists {
+                return Ok(false);
+            }
+        }
+
+        sqlx::query("delete from timesheets where workplace_id = $1")
+            .bind(workplace.id)
+            .execute(&mut *tx)
+            .await
+            .inspect_err(|e| log::error!("Failed to delete timesheets: {:?}", e))?;
+        sqlx::query("delete from calendar_feeds where workplace_id = $1")
+            .bind(workplace.id)
+            .execute(&mut *tx)
+            .await
+            .inspect_err(|e| log::error!("Failed to delete calendar_feeds: {:?}", e))?;
+        sqlx::query("delete from workplace_memberships where workplace_id = $1")
+            .bind(workplace.id)
+            .execute(&mut *tx)
+            .await
+            .inspect_err(|e| log::error!("Failed to delete workplace_memberships: {:?}", e))?;
+        tx.commit()
+            .await
+            .inspect_err(|e| log::error!("Failed to commit transaction: {:?}", e))?;
+        Ok(true)
+    }
 }
@@ -0,0 +1,101 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{Executor, Sqlite};
+
+use crate::{
+    errors::DatabaseError,
+    models::{Role, UserId, Workplace, WorkplaceMembership},
+    repositories::WorkplaceMembershipRepository,
+};
+
+pub struct RdbWorkplaceMembershipRepository<'a, T: Executor<'a>> {
+    executor: T,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> RdbWorkplaceMembershipRepository<'a, T>
+where
+    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+{
+    pub fn new(executor: T) -> Self {
+        Self {
+            executor,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<'a, T> WorkplaceMembershipRepository for RdbWorkplaceMembershipRepository<'a, T>
+where
+    T: Executor<'a, Database = Sqlite> + Copy + Sync,
+{
+    async fn list(&self, workplace: &Workplace) -> Result<Vec<WorkplaceMembership>, DatabaseError> {
+        let statement = "select workplace_id, user_id, role from (select id as workplace_id, user_id, 'owner' as role, 0 as position from workplaces where id = $1 union all select workplace_id, user_id, role, id as position from workplace_memberships where workplace_id = $1) where $2 or user_id = $3 order by position";
+        let memberships: Vec<WorkplaceMembership> = sqlx::query_as(statement)
+            .bind(workplace.id)
+            .bind(workplace.viewer.role.manages_attendance())
+            .bind(workplace.viewer.user_id)
+            .fetch_all(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to query workplace_memberships: {:?}", e))?;
+        Ok(memberships)
+    }
+
+    async fn create(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        role: Role,
+    ) -> Result<WorkplaceMembership, DatabaseError> {
+        let statement = "insert into workplace_memberships (workplace_id, user_id, role, created_at, updated_at) select $1, $2, $3, $4, $4 where exists (select 1 from workplaces where id = $1 and user_id = $5) returning workplace_id, user_id, role";
+        let now = Utc::now();
+        let membership: WorkplaceMembership = sqlx::query_as(statement)
+            .bind(workplace.id)
+            .bind(user_id)
+            .bind(role)
+            .bind(now)
+            .bind(workplace.viewer.user_id)
+            .fetch_one(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to create workplace_membership: {:?}", e))?;
+        Ok(membership)
+    }
+
+    async fn update(
+        &self,
+        workplace: &Workplace,
+        user_id: UserId,
+        role: Role,
+    ) -> Result<WorkplaceMembership, DatabaseError> {
+        let statement = "update workplace_memberships set role = $1, updated_at = $2 where workplace_id = $3 and user_id = $4 and exists (select 1 from workplaces where id = $3 and user_id = $5) returning workplace_id, user_id, role";
+        let now = Utc::now();
+        let membership: WorkplaceMembership = sqlx::query_as(statement)
+            .bind(role)
+            .bind(now)
+            .bind(workplace.id)
+            .bind(user_id)
+            .bind(workplace.viewer.user_id)
+            .fetch_one(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to update workplace_membership: {:?}", e))?;
+        Ok(membership)
+    }
+
+    async fn destroy(&self, workplace: &Workplace, user_id: UserId) -> Result<(), DatabaseError> {
+        let statement = "delete from workplace_memberships where workplace_id = $1 and user_id = $2 and exists (select 1 from workplaces where id = $1 and user_id = $3)";
+        let result = sqlx::query(statement)
+            .bind(workplace.id)
+            .bind(user_id)
+            .bind(workplace.viewer.user_id)
+            .execute(self.executor)
+            .await
+            .inspect_err(|e| log::error!("Failed to delete workplace_membership: {:?}", e))?;
+        if result.rows_affected() == 0 {
+            return Err(DatabaseError::RecordNotFound);
+        }
+        Ok(())
+    }
+}
@@ -3,6 +3,8 @@ use base64::{Engine as _, engine::general_purpose::STANDARD};
 use serde::{Deserialize, de};
 use std::ops::Deref;
 
+use crate::models::TokenKeyring;
+
 #[derive(Clone)]
 pub struct Base64Encoded(Vec<u8>);
 
@@ -45,10 +47,63 @@ impl<'de> de::Visitor<'de> for Base64EncodedVisitor {
     }
 }
 
+/// Retired digesting secrets, written as comma-separated `<key id>:<base64 secret>` pairs.
+#[derive(Clone, Default, Deserialize)]
+#[serde(try_from = "String")]
+pub struct PreviousSecretKeys(Vec<(String, Base64Encoded)>);
+
+impl PreviousSecretKeys {
+    pub fn new(keys: Vec<(String, Base64Encoded)>) -> Self {
+        Self(keys)
+    }
+}
+
+impl TryFrom<String> for PreviousSecretKeys {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let keys = value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (key_id, secret_key) = entry.split_once(':').ok_or_else(|| {
+                    anyhow::anyhow!("secret key must be written as <key id>:<base64>")
+                })?;
+                let secret_key = STANDARD.decode(secret_key.trim())?;
+                Ok((key_id.trim().to_string(), Base64Encoded(secret_key)))
+            })
+            .collect::<Result<Vec<(String, Base64Encoded)>>>()?;
+        Ok(Self(keys))
+    }
+}
+
 #[derive(Clone, Deserialize)]
 pub struct ApikeyConfig {
     #[serde(rename = "api_key_digesting_secret_key")]
     pub digesting_secret_key: Base64Encoded,
+
+    #[serde(
+        rename = "api_key_digesting_secret_key_id",
+        default = "ApikeyConfig::default_digesting_secret_key_id"
+    )]
+    pub digesting_secret_key_id: String,
+
+    #[serde(rename = "api_key_previous_digesting_secret_keys", default)]
+    pub previous_digesting_secret_keys: PreviousSecretKeys,
+}
+
+impl ApikeyConfig {
+    fn default_digesting_secret_key_id() -> String {
+        "primary".to_string()
+    }
+
+    pub fn keyring(&self) -> TokenKeyring {
+        self.previous_digesting_secret_keys.0.iter().fold(
+            TokenKeyring::new(&self.digesting_secret_key_id, &self.digesting_secret_key),
+            |keyring, (key_id, secret_key)| keyring.with_previous(key_id, secret_key),
+        )
+    }
 }
 
 #[derive(Clone, Deserialize)]
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use chrono::{TimeDelta, Utc};
+
+use crate::{AppState, repositories::RepositoryFactory};
+
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const IDEMPOTENCY_KEY_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Permanently removes deleted attendance records once they are older than the retention period.
+pub async fn purge_trash_periodically(app_state: AppState) {
+    let mut interval = actix_rt::time::interval(TRASH_PURGE_INTERVAL);
+    loop {
+        interval.tick().await;
+        purge_trash(&app_state).await;
+    }
+}
+
+pub async fn purge_trash(app_state: &AppState) {
+    let retention_period = TimeDelta::days(app_state.config.trash.retention_days.into());
+    let deleted_before = (Utc::now() - retention_period).into();
+
+    let repository = app_state.repositories.attendance_record();
+    match repository.purge_deleted(&deleted_before).await {
+        Ok(0) => {}
+        Ok(count) => log::info!("Purged {} deleted attendance_records", count),
+        Err(e) => log::error!("Failed to purge deleted attendance_records: {:?}", e),
+    }
+}
+
+/// Removes the responses stored for `Idempotency-Key`s once they are no longer replayed.
+pub async fn purge_idempotency_keys_periodically(app_state: AppState) {
+    let mut interval = actix_rt::time::interval(IDEMPOTENCY_KEY_PURGE_INTERVAL);
+    loop {
+        interval.tick().await;
+        purge_idempotency_keys(&app_state).await;
+    }
+}
+
+pub async fn purge_idempotency_keys(app_state: &AppState) {
+    let created_before = (Utc::now() - app_state.config.idempotency.window()).into();
+
+    let repository = app_state.repositories.idempotency_key();
+    match repository.purge_expired(&created_before).await {
+        Ok(0) => {}
+        Ok(count) => log::info!("Purged {} expired idempotency_keys", count),
+        Err(e) => log::error!("Failed to purge expired idempotency_keys: {:?}", e),
+    }
+}
@@ -0,0 +1,24 @@
+use actix_web::test::TestRequest;
+use azarole::access_log::request_line;
+
+#[test]
+fn request_line_of_calendar_feed() {
+    let request = TestRequest::get()
+        .uri("/calendar_feeds/AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8.ics")
+        .to_srv_request();
+    assert_eq!(
+        request_line(&request),
+        "GET /calendar_feeds/[FILTERED].ics HTTP/1.1"
+    );
+}
+
+#[test]
+fn request_line_of_other_paths() {
+    let request = TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins?on_conflict=merge")
+        .to_srv_request();
+    assert_eq!(
+        request_line(&request),
+        "POST /api/workplaces/1/clock_ins?on_conflict=merge HTTP/1.1"
+    );
+}
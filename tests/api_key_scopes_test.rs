@@ -0,0 +1,212 @@
+use actix_web::{App, http::StatusCode, test, web::Data};
+use azarole::models::ApiKey;
+use serde::Serialize;
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
+
+mod common;
+
+const VALID_API_KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+
+async fn restrict_api_key(pool: &SqlitePool, workplace_ids: &str, actions: &str) {
+    sqlx::query("update api_keys set workplace_ids = $1, actions = $2 where id = 1")
+        .bind(workplace_ids)
+        .bind(actions)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[derive(Serialize)]
+struct CreatingApiKeyForm {
+    name: String,
+    workplace_ids: String,
+    actions: String,
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn creating_scoped_api_key(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let params = CreatingApiKeyForm {
+        name: "kiosk".to_owned(),
+        workplace_ids: "2,1".to_owned(),
+        actions: "read,clock_in".to_owned(),
+    };
+    let request = test::TestRequest::post()
+        .uri("/api_keys")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(&params)
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let created_key: ApiKey = sqlx::query_as(
+        "select id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at from api_keys where user_id = 1",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(String::from(created_key.scope.workplace_ids), "1,2");
+    assert_eq!(String::from(created_key.scope.actions), "clock_in,read");
+
+    let request = test::TestRequest::get()
+        .uri("/api_keys")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response_json: Value = test::read_body_json(response).await;
+    let api_key_node = &response_json["apiKeys"][0];
+    assert_eq!(api_key_node["workplaceIds"], json!("1,2"));
+    assert_eq!(api_key_node["actions"], json!("clock_in,read"));
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn creating_api_key_scoped_to_inaccessible_workplace(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    for (workplace_ids, actions) in [("3", "clock_in"), ("1", "clock_in,delete")] {
+        let params = CreatingApiKeyForm {
+            name: "kiosk".to_owned(),
+            workplace_ids: workplace_ids.to_owned(),
+            actions: actions.to_owned(),
+        };
+        let request = test::TestRequest::post()
+            .uri("/api_keys")
+            .insert_header(("Cookie", cookie_value.clone()))
+            .set_form(&params)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let (count,): (i64,) = sqlx::query_as("select count(*) from api_keys")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn api_key_restricted_to_workplace(pool: SqlitePool) {
+    restrict_api_key(&pool, "1", "").await;
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/2/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .to_request();
+    let result = test::try_call_service(&app, request).await;
+    let response = result.unwrap_err().error_response();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let (count,): (i64,) =
+        sqlx::query_as("select count(*) from attendance_records where workplace_id = 2")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn api_key_restricted_to_clock_in(pool: SqlitePool) {
+    restrict_api_key(&pool, "", "clock_in").await;
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/2/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    for uri in [
+        "/api/workplaces/2/clock_outs",
+        "/api/workplaces/2/break_starts",
+    ] {
+        let request = test::TestRequest::post()
+            .uri(uri)
+            .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+            .to_request();
+        let result = test::try_call_service(&app, request).await;
+        let response = result.unwrap_err().error_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    let request = test::TestRequest::get()
+        .uri("/api/workplaces/2/attendance_records")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .to_request();
+    let result = test::try_call_service(&app, request).await;
+    let response = result.unwrap_err().error_response();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records", "api_keys"))]
+async fn read_only_api_key(pool: SqlitePool) {
+    restrict_api_key(&pool, "", "read").await;
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let request = test::TestRequest::get()
+        .uri("/api/workplaces/1/attendance_records?year=2026&month=1")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let records = response_json["attendanceRecords"].as_array().unwrap();
+    assert_eq!(records.len(), 2);
+
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .to_request();
+    let result = test::try_call_service(&app, request).await;
+    let response = result.unwrap_err().error_response();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
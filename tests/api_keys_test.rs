@@ -1,5 +1,10 @@
+#![allow(clippy::iter_count)]
+
 use actix_web::{App, http::StatusCode, test, web::Data};
-use azarole::models::{ApiKey, TokenDigester};
+use azarole::{
+    models::{ApiKey, TokenDigester},
+    secrets::{Base64Encoded, PreviousSecretKeys},
+};
 use serde::Serialize;
 use serde_json::{Value, json};
 use sqlx::SqlitePool;
@@ -54,6 +59,8 @@ async fn listing_api_keys(pool: SqlitePool) {
                 "id": 1,
                 "name": "test-api-key-01",
                 "createdAt": "2026-02-02T01:02:03Z",
+                "workplaceIds": "",
+                "actions": "",
             },
         ],
         // deprecated
@@ -62,6 +69,8 @@ async fn listing_api_keys(pool: SqlitePool) {
                 "id": 1,
                 "name": "test-api-key-01",
                 "createdAt": "2026-02-02T01:02:03Z",
+                "workplaceIds": "",
+                "actions": "",
             },
         ],
     });
@@ -100,7 +109,7 @@ async fn creating_api_key_without_signin(pool: SqlitePool) {
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 
     let existing_keys: Vec<ApiKey> =
-        sqlx::query_as("select id, user_id, name, digest, created_at from api_keys")
+        sqlx::query_as("select id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at from api_keys")
             .bind(1)
             .fetch_all(&pool)
             .await
@@ -133,7 +142,7 @@ async fn creating_api_key(pool: SqlitePool) {
     assert_eq!(response.status(), StatusCode::OK);
 
     let created_key: ApiKey =
-        sqlx::query_as("select id, user_id, name, digest, created_at from api_keys where user_id = $1 order by created_at limit 1")
+        sqlx::query_as("select id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at from api_keys where user_id = $1 order by created_at limit 1")
             .bind(1)
             .fetch_one(&pool)
             .await
@@ -172,7 +181,7 @@ async fn deleting_api_key_without_signin(pool: SqlitePool) {
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 
     let result: Option<ApiKey> =
-        sqlx::query_as("select id, user_id, name, digest, created_at from api_keys where id = $1")
+        sqlx::query_as("select id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at from api_keys where id = $1")
             .bind(1)
             .fetch_optional(&pool)
             .await
@@ -201,10 +210,342 @@ async fn deleting_api_key(pool: SqlitePool) {
     assert!(response.status().is_success());
 
     let result: Option<ApiKey> =
-        sqlx::query_as("select id, user_id, name, digest, created_at from api_keys where id = $1")
+        sqlx::query_as("select id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at from api_keys where id = $1")
             .bind(1)
             .fetch_optional(&pool)
             .await
             .unwrap();
     assert!(result.is_none());
 }
+
+#[derive(Serialize)]
+struct CreatingExpiringApiKeyForm {
+    name: String,
+    expires_at: String,
+}
+
+#[sqlx::test(fixtures("users"))]
+async fn creating_api_key_with_expiry(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let params = CreatingExpiringApiKeyForm {
+        name: "expired".to_owned(),
+        expires_at: "2020-01-01T00:00:00Z".to_owned(),
+    };
+    let request = test::TestRequest::post()
+        .uri("/api_keys")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(&params)
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let params = CreatingExpiringApiKeyForm {
+        name: "temporary".to_owned(),
+        expires_at: "2999-12-31T23:59:59Z".to_owned(),
+    };
+    let request = test::TestRequest::post()
+        .uri("/api_keys")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(&params)
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri("/api_keys")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response_json: Value = test::read_body_json(response).await;
+    let api_keys = response_json["apiKeys"].as_array().unwrap();
+    assert_eq!(api_keys.len(), 1);
+    assert_eq!(api_keys[0]["name"], json!("temporary"));
+    assert_eq!(api_keys[0]["expiresAt"], json!("2999-12-31T23:59:59Z"));
+    assert!(api_keys[0].get("lastUsedAt").is_none());
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn using_api_key_records_last_use(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .peer_addr("192.0.2.10:50000".parse().unwrap())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/api_keys")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response_json: Value = test::read_body_json(response).await;
+    let api_key_node = &response_json["apiKeys"][0];
+    assert!(api_key_node["lastUsedAt"].is_string());
+    assert_eq!(api_key_node["lastUsedIp"], json!("192.0.2.10"));
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn using_expired_api_key(pool: SqlitePool) {
+    sqlx::query("update api_keys set expires_at = '2026-03-01T00:00:00Z' where id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .to_request();
+    let result = test::try_call_service(&app, request).await;
+    let response = result.unwrap_err().error_response();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let (last_used_at,): (Option<String>,) =
+        sqlx::query_as("select last_used_at from api_keys where id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(last_used_at.is_none());
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn using_api_key_after_rotating_digesting_secret(pool: SqlitePool) {
+    let mut app_state = common::create_app_state(pool.clone());
+    let previous_secret_key = app_state.secrets.api_key.digesting_secret_key.clone();
+    let current_secret_key = Base64Encoded::new((32..64).collect());
+    app_state.secrets.api_key.digesting_secret_key = current_secret_key.clone();
+    app_state.secrets.api_key.digesting_secret_key_id = "2026-10".to_string();
+    app_state.secrets.api_key.previous_digesting_secret_keys =
+        PreviousSecretKeys::new(vec![("primary".to_string(), previous_secret_key)]);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    for uri in [
+        "/api/workplaces/1/clock_ins",
+        "/api/workplaces/1/clock_outs",
+    ] {
+        let request = test::TestRequest::post()
+            .uri(uri)
+            .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let api_key: ApiKey = sqlx::query_as("select id, user_id, name, public_id, digest, digest_key_id, previous_digest, previous_digest_expires_at, created_at, workplace_ids, actions, last_used_at, last_used_ip, expires_at from api_keys where id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let expected_digest = TokenDigester::new(&current_secret_key)
+        .digest_token(valid_api_key)
+        .unwrap();
+    assert_eq!(api_key.digest, expected_digest);
+    assert_eq!(api_key.digest_key_id.as_deref(), Some("2026-10"));
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn using_prefixed_api_key(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let params = CreatingApiKeyForm {
+        name: "shortcut".to_owned(),
+    };
+    let request = test::TestRequest::post()
+        .uri("/api_keys")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(&params)
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response_json: Value = test::read_body_json(response).await;
+    let public_id = response_json["apiKey"]["publicId"].as_str().unwrap();
+    let token = response_json["apiKey"]["token"].as_str().unwrap();
+    assert_eq!(public_id.len(), 12);
+    assert!(token.starts_with(&format!("azr_{public_id}_")));
+
+    let request = test::TestRequest::get()
+        .uri("/api_keys")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let listing_json: Value = test::read_body_json(response).await;
+    assert_eq!(listing_json["apiKeys"][0]["publicId"], json!(public_id));
+
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let forged_token = format!("azr_{public_id}_forged");
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_outs")
+        .insert_header(("Authorization", format!("Bearer {forged_token}")))
+        .to_request();
+    let result = test::try_call_service(&app, request).await;
+    let response = result.unwrap_err().error_response();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn rotating_api_key(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/api_keys/1/rotate")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let api_key_node = &response_json["apiKey"];
+    assert_eq!(api_key_node["id"], json!(1));
+    assert_eq!(api_key_node["name"], json!("test-api-key-01"));
+    let new_token = api_key_node["token"].as_str().unwrap().to_owned();
+
+    let old_token = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    for (token, uri) in [
+        (new_token.as_str(), "/api/workplaces/1/clock_ins"),
+        (old_token, "/api/workplaces/1/clock_outs"),
+    ] {
+        let request = test::TestRequest::post()
+            .uri(uri)
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    sqlx::query(
+        "update api_keys set previous_digest_expires_at = '2026-01-01T00:00:00Z' where id = 1",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {old_token}")))
+        .to_request();
+    let result = test::try_call_service(&app, request).await;
+    let response = result.unwrap_err().error_response();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {new_token}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[sqlx::test(fixtures("users", "api_keys"))]
+async fn rotating_expired_api_key(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("update api_keys set expires_at = '2026-01-01T00:00:00Z' where id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/api_keys/1/rotate")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let (previous_digest,): (Option<String>,) =
+        sqlx::query_as("select previous_digest from api_keys where id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(previous_digest.is_none());
+}
+
+#[sqlx::test(fixtures("users", "api_keys"))]
+async fn rotating_api_key_of_another_user(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(2);
+    let request = test::TestRequest::post()
+        .uri("/api_keys/1/rotate")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let (previous_digest,): (Option<String>,) =
+        sqlx::query_as("select previous_digest from api_keys where id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(previous_digest.is_none());
+}
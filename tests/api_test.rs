@@ -1,5 +1,10 @@
+#![allow(clippy::iter_count)]
+
 use actix_web::{App, http::StatusCode, test, web::Data};
-use azarole::models::{AttendanceRecord, attendance_record};
+use azarole::models::{
+    AttendanceRecord, AttendanceRecordAudit, AuditAction, Channel, attendance_record,
+};
+use serde_json::{Value, json};
 use sqlx::SqlitePool;
 
 mod common;
@@ -46,7 +51,7 @@ async fn clock_in_with_api_key(pool: SqlitePool) {
 
     assert_eq!(response.status(), StatusCode::CREATED);
 
-    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, event, recorded_at from attendance_records where workplace_id = 1 order by recorded_at desc")
+    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = 1 order by recorded_at desc")
         .fetch_all(&pool)
         .await.unwrap();
     assert_eq!(records.iter().count(), 1);
@@ -78,7 +83,7 @@ async fn clock_in_with_invalid_api_key(pool: SqlitePool) {
     let response = error.error_response();
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 
-    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, event, recorded_at from attendance_records where workplace_id = $1")
+    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = $1")
         .fetch_all(&pool)
         .await.unwrap();
     assert_eq!(records.iter().count(), 0);
@@ -105,12 +110,46 @@ async fn clock_in_on_another_ones_workplace(pool: SqlitePool) {
     let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
-    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, event, recorded_at from attendance_records where workplace_id = 3 order by recorded_at desc")
+    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = 3 order by recorded_at desc")
         .fetch_all(&pool)
         .await.unwrap();
     assert_eq!(records.iter().count(), 0);
 }
 
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn clock_in_tapped_twice_at_once(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let requests = [0, 1].map(|_| {
+        test::TestRequest::post()
+            .uri("/api/workplaces/1/clock_ins")
+            .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+            .to_request()
+    });
+    let [first, second] = requests;
+    let (first, second) = futures_util::join!(
+        test::call_service(&app, first),
+        test::call_service(&app, second)
+    );
+
+    let mut statuses = [first.status(), second.status()];
+    statuses.sort();
+    assert_eq!(statuses, [StatusCode::CREATED, StatusCode::CONFLICT]);
+
+    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = 1")
+        .fetch_all(&pool)
+        .await.unwrap();
+    assert_eq!(records.len(), 1);
+}
+
 #[sqlx::test(fixtures("users", "workplaces"))]
 async fn clock_out_without_api_key(pool: SqlitePool) {
     let app_state = common::create_app_state(pool);
@@ -144,6 +183,11 @@ async fn clock_out_with_api_key(pool: SqlitePool) {
     )
     .await;
 
+    sqlx::query("insert into attendance_records (workplace_id, user_id, event, recorded_at, created_at) values (1, 1, 'clock-in', '2026-01-26T00:00:00Z', '2026-01-26T00:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
     let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
     let request = test::TestRequest::post()
         .uri("/api/workplaces/1/clock_outs")
@@ -153,10 +197,10 @@ async fn clock_out_with_api_key(pool: SqlitePool) {
 
     assert_eq!(response.status(), StatusCode::CREATED);
 
-    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, event, recorded_at from attendance_records where workplace_id = 1 order by recorded_at desc")
+    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = 1 order by recorded_at desc")
         .fetch_all(&pool)
         .await.unwrap();
-    assert_eq!(records.iter().count(), 1);
+    assert_eq!(records.iter().count(), 2);
 
     let attendance = records.first().unwrap();
     assert_eq!(attendance.event, attendance_record::Event::ClockOut);
@@ -185,7 +229,7 @@ async fn clock_out_with_invalid_api_key(pool: SqlitePool) {
     let response = error.error_response();
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 
-    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, event, recorded_at from attendance_records where workplace_id = $1")
+    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = $1")
         .fetch_all(&pool)
         .await.unwrap();
     assert_eq!(records.iter().count(), 0);
@@ -212,8 +256,486 @@ async fn clock_out_on_another_ones_workplace(pool: SqlitePool) {
     let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
-    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, event, recorded_at from attendance_records where workplace_id = 3 order by recorded_at desc")
+    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = 3 order by recorded_at desc")
         .fetch_all(&pool)
         .await.unwrap();
     assert_eq!(records.iter().count(), 0);
 }
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn clock_in_in_workplace_timezone(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("update workplaces set timezone = 'Europe/London' where id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let local_recorded_at = response_json["attendanceRecord"]["localRecordedAt"]
+        .as_str()
+        .unwrap();
+    assert!(local_recorded_at.ends_with("+00:00") || local_recorded_at.ends_with("+01:00"));
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn clock_in_while_clocked_in(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("insert into attendance_records (workplace_id, user_id, event, recorded_at, created_at) values (1, 1, 'clock-in', '2026-01-26T00:00:00Z', '2026-01-26T00:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "error": "conflict",
+        "reason": "already-clocked-in",
+    });
+    assert_eq!(response_json, expected_json);
+
+    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = 1")
+        .fetch_all(&pool)
+        .await.unwrap();
+    assert_eq!(records.len(), 1);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn clock_out_while_clocked_out(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_outs")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["reason"], "already-clocked-out");
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn clock_in_while_clocked_in_with_merging(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (7, 1, 1, 'clock-in', '2026-01-26T00:00:00Z', '2026-01-26T00:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins?on_conflict=merge")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["attendanceRecord"]["id"], 7);
+    assert_eq!(
+        response_json["attendanceRecord"]["recordedAt"],
+        "2026-01-26T00:00:00Z"
+    );
+
+    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = 1")
+        .fetch_all(&pool)
+        .await.unwrap();
+    assert_eq!(records.len(), 1);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn clock_in_is_audited_with_api_key(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let audits: Vec<AttendanceRecordAudit> = sqlx::query_as("select id, attendance_record_id, workplace_id, user_id, action, channel, api_key_id, previous_event, previous_recorded_at, event, recorded_at, created_at from attendance_record_audits")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(audits.len(), 1);
+
+    let audit = audits.first().unwrap();
+    assert_eq!(audit.action, AuditAction::Create);
+    assert_eq!(audit.channel, Channel::ApiKey);
+    assert!(audit.api_key_id.is_some());
+    assert_eq!(audit.event, Some(attendance_record::Event::ClockIn));
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn break_start_and_end_with_api_key(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("insert into attendance_records (workplace_id, user_id, event, recorded_at, created_at) values (1, 1, 'clock-in', '2026-01-26T00:00:00Z', '2026-01-26T00:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    for uri in [
+        "/api/workplaces/1/break_starts",
+        "/api/workplaces/1/break_ends",
+    ] {
+        let request = test::TestRequest::post()
+            .uri(uri)
+            .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = 1 order by recorded_at, id")
+        .fetch_all(&pool)
+        .await.unwrap();
+    let events = records
+        .into_iter()
+        .map(|record| record.event)
+        .collect::<Vec<attendance_record::Event>>();
+    assert_eq!(
+        events,
+        vec![
+            attendance_record::Event::ClockIn,
+            attendance_record::Event::BreakStart,
+            attendance_record::Event::BreakEnd,
+        ]
+    );
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn break_start_while_clocked_out(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/break_starts")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["reason"], "not-clocked-in");
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn clock_in_retried_with_idempotency_key(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let mut response_jsons: Vec<Value> = Vec::new();
+    for _ in 0..2 {
+        let request = test::TestRequest::post()
+            .uri("/api/workplaces/1/clock_ins")
+            .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+            .insert_header(("Idempotency-Key", "nfc-tag-0001"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        response_jsons.push(test::read_body_json(response).await);
+    }
+    assert_eq!(response_jsons[0], response_jsons[1]);
+
+    let records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = 1")
+        .fetch_all(&pool)
+        .await.unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(
+        response_jsons[0]["attendanceRecord"]["id"],
+        json!(records[0].id)
+    );
+
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_outs")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .insert_header(("Idempotency-Key", "nfc-tag-0001"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["reason"], "idempotency-key-reused");
+
+    // asking for another conflict policy is another request as well
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins?on_conflict=merge")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .insert_header(("Idempotency-Key", "nfc-tag-0001"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["reason"], "idempotency-key-reused");
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn clock_in_with_expired_idempotency_key(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .insert_header(("Idempotency-Key", "nfc-tag-0001"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    sqlx::query("update idempotency_keys set created_at = '2026-01-01T00:00:00Z'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .insert_header(("Idempotency-Key", "nfc-tag-0001"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["reason"], "already-clocked-in");
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn clock_in_while_idempotency_key_is_pending(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    // the first request with the key is still running
+    sqlx::query("insert into idempotency_keys (api_key_id, user_id, key, request_uri, created_at) values (1, 1, 'nfc-tag-0001', '/api/workplaces/1/clock_ins', $1)")
+        .bind(chrono::Utc::now())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .insert_header(("Idempotency-Key", "nfc-tag-0001"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["reason"], "idempotency-key-in-progress");
+
+    let count: i64 = sqlx::query_scalar("select count(*) from attendance_records")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn failed_clock_releases_idempotency_key(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    // a retry runs the request again instead of finding the key in progress
+    for _ in 0..2 {
+        let request = test::TestRequest::post()
+            .uri("/api/workplaces/1/break_starts")
+            .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+            .insert_header(("Idempotency-Key", "nfc-tag-0001"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response_json: Value = test::read_body_json(response).await;
+        assert_eq!(response_json["reason"], "not-clocked-in");
+    }
+
+    let count: i64 = sqlx::query_scalar("select count(*) from idempotency_keys")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[sqlx::test(fixtures("users", "api_keys"))]
+async fn purging_idempotency_keys(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+
+    let recently_created_at = chrono::Utc::now() - chrono::TimeDelta::hours(1);
+    let long_ago_created_at = chrono::Utc::now() - chrono::TimeDelta::days(2);
+    sqlx::query("insert into idempotency_keys (api_key_id, user_id, key, request_uri, response_status, response_body, created_at, completed_at) values (1, 1, 'recent', '/api/workplaces/1/clock_ins', 201, '{}', $1, $1), (1, 1, 'long-ago', '/api/workplaces/1/clock_ins', 201, '{}', $2, $2)")
+        .bind(recently_created_at)
+        .bind(long_ago_created_at)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    azarole::tasks::purge_idempotency_keys(&app_state).await;
+
+    let keys: Vec<(String,)> = sqlx::query_as("select key from idempotency_keys")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(keys, vec![("recent".to_string(),)]);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn clock_in_whose_response_cannot_be_stored(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("create trigger failing_completion before update on idempotency_keys begin select raise(abort, 'failing'); end")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/clock_ins")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .insert_header(("Idempotency-Key", "nfc-tag-0001"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // the key is not left in progress
+    let count: i64 = sqlx::query_scalar("select count(*) from idempotency_keys")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn failed_clock_whose_idempotency_key_cannot_be_released(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("create trigger failing_release before delete on idempotency_keys begin select raise(abort, 'failing'); end")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::post()
+        .uri("/api/workplaces/1/break_starts")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .insert_header(("Idempotency-Key", "nfc-tag-0001"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["reason"], "not-clocked-in");
+}
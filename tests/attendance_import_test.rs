@@ -0,0 +1,177 @@
+use actix_multipart::test::create_form_data_payload_and_headers;
+use actix_web::{App, http::StatusCode, test, web::Bytes, web::Data};
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
+
+mod common;
+
+fn import_request(uri: &str, csv: &'static str) -> test::TestRequest {
+    let (body, headers) = create_form_data_payload_and_headers(
+        "file",
+        Some("attendances.csv".to_owned()),
+        None,
+        Bytes::from_static(csv.as_bytes()),
+    );
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let mut request = test::TestRequest::post()
+        .uri(uri)
+        .insert_header(("Cookie", cookie_value));
+    for (name, value) in headers.iter() {
+        request = request.insert_header((name.clone(), value.clone()));
+    }
+    request.set_payload(body)
+}
+
+async fn count_records(pool: &SqlitePool) -> i64 {
+    sqlx::query_scalar("select count(*) from attendance_records")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_import(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let csv = "event,datetime\nclock-in,2025-12-01 09:00\nbreak-start,2025-12-01 12:00\nbreak-end,2025-12-01 13:00\nclock-out,2025-12-01T09:30:00Z\n";
+    let request = import_request("/workplaces/1/attendance_records/import", csv).to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["dryRun"], false);
+    let attendance_records = response_json["attendanceRecords"].as_array().unwrap();
+    assert_eq!(attendance_records.len(), 4);
+    assert_eq!(attendance_records[0]["event"], "clock-in");
+    assert_eq!(attendance_records[0]["recordedAt"], "2025-12-01T00:00:00Z");
+    assert_eq!(attendance_records[3]["event"], "clock-out");
+    assert_eq!(attendance_records[3]["recordedAt"], "2025-12-01T09:30:00Z");
+
+    assert_eq!(count_records(&pool).await, 6);
+    let audit_count: i64 =
+        sqlx::query_scalar("select count(*) from attendance_record_audits where action = 'create'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(audit_count, 4);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_import_dry_run(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let csv = "event,datetime\nclock-out,2025-12-01 18:00\nclock-in,2025-12-01 09:00\n";
+    let request =
+        import_request("/workplaces/1/attendance_records/import?dry_run=true", csv).to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "dryRun": true,
+        "attendanceRecords": [
+            {
+                "line": 3,
+                "event": "clock-in",
+                "recordedAt": "2025-12-01T00:00:00Z",
+            },
+            {
+                "line": 2,
+                "event": "clock-out",
+                "recordedAt": "2025-12-01T09:00:00Z",
+            },
+        ],
+    });
+    assert_eq!(response_json, expected_json);
+    assert_eq!(count_records(&pool).await, 2);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_import_with_invalid_rows(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let csv =
+        "event,datetime\nclock-in,2025-12-01 09:00\nlunch,2025-12-01 12:00\nclock-out,yesterday\n";
+    let request = import_request("/workplaces/1/attendance_records/import", csv).to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(
+        response_json["errors"],
+        json!([
+            { "line": 3, "reason": "invalid-event" },
+            { "line": 4, "reason": "invalid-datetime" },
+        ])
+    );
+
+    // Fine row by row, but the second clock-in conflicts with the first one.
+    let csv = "event,datetime\nclock-in,2025-12-01 09:00\nclock-in,2025-12-02 09:00\nclock-out,2025-12-02 18:00\n";
+    let request = import_request("/workplaces/1/attendance_records/import", csv).to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(
+        response_json["errors"],
+        json!([
+            { "line": 3, "reason": "already-clocked-in" },
+        ])
+    );
+
+    // Fine on its own, but leaves the existing clock-in on 2026-01-26 without a clock-out before it.
+    let csv = "event,datetime
+clock-in,2026-01-26T09:00:00Z
+";
+    let request = import_request("/workplaces/1/attendance_records/import", csv).to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(
+        response_json["errors"],
+        json!([
+            { "line": 2, "reason": "already-clocked-in" },
+        ])
+    );
+    assert_eq!(count_records(&pool).await, 2);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_import_with_other_user(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let csv = "event,datetime\nclock-in,2025-12-01 09:00\n";
+    let request = import_request("/workplaces/3/attendance_records/import", csv).to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(count_records(&pool).await, 2);
+}
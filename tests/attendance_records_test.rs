@@ -1,6 +1,8 @@
+#![allow(clippy::iter_count)]
+
 use actix_web::{App, http::StatusCode, test, web::Data};
 use azarole::models::{AttendanceRecord, attendance_record};
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, TimeDelta, Utc};
 use serde::Serialize;
 use serde_json::{Value, json};
 use sqlx::SqlitePool;
@@ -68,13 +70,14 @@ async fn attendance_record_creation(pool: SqlitePool) {
     let expected_json = json!({
         "attendanceRecord": {
             "id": 1,
+            "userId": 1,
             "event": "clock-in",
             "recordedAt": now.to_utc(),
         },
     });
     assert_eq!(response_json, expected_json);
 
-    let attendance_records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, event, recorded_at from attendance_records where workplace_id = $1 order by created_at desc")
+    let attendance_records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = $1 order by created_at desc")
         .bind(1)
         .fetch_all(&pool)
         .await
@@ -110,7 +113,7 @@ async fn attendance_record_creation_with_other_user(pool: SqlitePool) {
     let response = test::call_service(&app, request).await;
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
-    let attendance_records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, event, recorded_at from attendance_records where workplace_id = $1 order by created_at desc")
+    let attendance_records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = $1 order by created_at desc")
         .bind(1)
         .fetch_all(&pool)
         .await
@@ -140,7 +143,7 @@ async fn attendance_record_deletion_without_signin(pool: SqlitePool) {
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 
     let attendance_record: Option<AttendanceRecord> = sqlx::query_as(
-        "select id, workplace_id, event, recorded_at from attendance_records where id = $1",
+        "select id, workplace_id, user_id, event, recorded_at from attendance_records where id = $1",
     )
     .bind(1)
     .fetch_optional(&pool)
@@ -170,7 +173,7 @@ async fn attendance_record_deletion(pool: SqlitePool) {
     assert_eq!(response.status(), StatusCode::OK);
 
     let attendance_record: Option<AttendanceRecord> = sqlx::query_as(
-        "select id, workplace_id, event, recorded_at from attendance_records where id = $1",
+        "select id, workplace_id, user_id, event, recorded_at from attendance_records where id = $1 and deleted_at is null",
     )
     .bind(1)
     .fetch_optional(&pool)
@@ -178,6 +181,16 @@ async fn attendance_record_deletion(pool: SqlitePool) {
     .unwrap();
 
     assert!(attendance_record.is_none());
+
+    let attendance_record: AttendanceRecord = sqlx::query_as(
+        "select id, workplace_id, user_id, event, recorded_at, deleted_at from attendance_records where id = $1",
+    )
+    .bind(1)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    assert!(attendance_record.deleted_at.is_some());
 }
 
 #[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
@@ -200,7 +213,7 @@ async fn attendance_record_deletion_with_other_user(pool: SqlitePool) {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
     let attendance_record: Option<AttendanceRecord> = sqlx::query_as(
-        "select id, workplace_id, event, recorded_at from attendance_records where id = $1",
+        "select id, workplace_id, user_id, event, recorded_at from attendance_records where id = $1",
     )
     .bind(1)
     .fetch_optional(&pool)
@@ -209,3 +222,1506 @@ async fn attendance_record_deletion_with_other_user(pool: SqlitePool) {
 
     assert!(attendance_record.is_some());
 }
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_listing(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "year": 2026,
+        "month": 1,
+        "from": "2026-01-01",
+        "to": "2026-01-31",
+        "workplace": {
+            "id": 1,
+            "name": "workplace-01-for-user-01",
+            "timezone": "Asia/Tokyo",
+            "breakRules": "360:45,480:60",
+            "overtimeRules": {
+                "dailyThresholdMinutes": 480,
+                "weeklyThresholdMinutes": 2400,
+                "restDays": "sun",
+            },
+            "closingDay": 31,
+            "role": "owner",
+        },
+        "attendanceRecords": [
+            {
+                "id": 1,
+                "userId": 1,
+                "event": "clock-in",
+                "recordedAt": "2026-01-26T12:34:56Z",
+            },
+            {
+                "id": 2,
+                "userId": 1,
+                "event": "clock-out",
+                "recordedAt": "2026-01-26T13:14:15Z",
+            },
+        ],
+        "workSessions": [
+            {
+                "userId": 1,
+                "clockInId": 1,
+                "clockOutId": 2,
+                "startedAt": "2026-01-26T12:34:56Z",
+                "endedAt": "2026-01-26T13:14:15Z",
+                "durationSeconds": 2359,
+                "breakSeconds": 0,
+                "workedSeconds": 2359,
+                "breaks": [],
+                "status": "complete",
+            },
+        ],
+    });
+    assert_eq!(response_json, expected_json);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_listing_with_unmatched_events(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (3, 1, 1, 'clock-out', '2026-01-27T01:00:00Z', '2026-01-27T01:00:00Z'), (4, 1, 1, 'clock-in', '2026-01-28T00:00:00Z', '2026-01-28T00:00:00Z'), (5, 1, 1, 'clock-in', '2026-01-29T00:00:00Z', '2026-01-29T00:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!([
+        {
+            "userId": 1,
+            "clockInId": 1,
+            "clockOutId": 2,
+            "startedAt": "2026-01-26T12:34:56Z",
+            "endedAt": "2026-01-26T13:14:15Z",
+            "durationSeconds": 2359,
+            "breakSeconds": 0,
+            "workedSeconds": 2359,
+            "breaks": [],
+            "status": "complete",
+        },
+        {
+            "userId": 1,
+            "clockInId": null,
+            "clockOutId": 3,
+            "startedAt": null,
+            "endedAt": "2026-01-27T01:00:00Z",
+            "durationSeconds": null,
+            "breakSeconds": 0,
+            "workedSeconds": null,
+            "breaks": [],
+            "status": "missing-clock-in",
+        },
+        {
+            "userId": 1,
+            "clockInId": 4,
+            "clockOutId": null,
+            "startedAt": "2026-01-28T00:00:00Z",
+            "endedAt": null,
+            "durationSeconds": null,
+            "breakSeconds": 0,
+            "workedSeconds": null,
+            "breaks": [],
+            "status": "missing-clock-out",
+        },
+        {
+            "userId": 1,
+            "clockInId": 5,
+            "clockOutId": null,
+            "startedAt": "2026-01-29T00:00:00Z",
+            "endedAt": null,
+            "durationSeconds": null,
+            "breakSeconds": 0,
+            "workedSeconds": null,
+            "breaks": [],
+            "status": "missing-clock-out",
+        },
+    ]);
+    assert_eq!(response_json["workSessions"], expected_json);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_summary(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (3, 1, 1, 'clock-in', '2026-01-26T23:00:00Z', '2026-01-26T23:00:00Z'), (4, 1, 1, 'clock-out', '2026-01-27T08:30:00Z', '2026-01-27T08:30:00Z'), (5, 1, 1, 'clock-in', '2026-01-27T09:00:00Z', '2026-01-27T09:00:00Z'), (6, 1, 1, 'clock-out', '2026-01-27T10:00:00Z', '2026-01-27T10:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/summary?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let days = response_json["summary"]["days"].as_array().unwrap();
+    assert_eq!(days.len(), 31);
+    assert_eq!(
+        days[0],
+        json!({
+            "date": "2026-01-01",
+            "firstClockIn": null,
+            "lastClockOut": null,
+            "breakMinutes": 0,
+            "deductedBreakMinutes": 0,
+            "workedMinutes": 0,
+            "sessionCount": 0,
+            "autoAdjusted": false,
+            "breakdown": {
+                "regularMinutes": 0,
+                "overtimeMinutes": 0,
+                "lateNightMinutes": 0,
+                "restDayMinutes": 0,
+            },
+        })
+    );
+    assert_eq!(
+        days[25],
+        json!({
+            "date": "2026-01-26",
+            "firstClockIn": "2026-01-26T12:34:56Z",
+            "lastClockOut": "2026-01-26T13:14:15Z",
+            "breakMinutes": 0,
+            "deductedBreakMinutes": 0,
+            "workedMinutes": 39,
+            "sessionCount": 1,
+            "autoAdjusted": false,
+            "breakdown": {
+                "regularMinutes": 39,
+                "overtimeMinutes": 0,
+                "lateNightMinutes": 14,
+                "restDayMinutes": 0,
+            },
+        })
+    );
+    assert_eq!(
+        days[26],
+        json!({
+            "date": "2026-01-27",
+            "firstClockIn": "2026-01-26T23:00:00Z",
+            "lastClockOut": "2026-01-27T10:00:00Z",
+            "breakMinutes": 0,
+            "deductedBreakMinutes": 60,
+            "workedMinutes": 570,
+            "sessionCount": 2,
+            "autoAdjusted": true,
+            "breakdown": {
+                "regularMinutes": 480,
+                "overtimeMinutes": 90,
+                "lateNightMinutes": 0,
+                "restDayMinutes": 0,
+            },
+        })
+    );
+    assert_eq!(
+        response_json["summary"]["total"],
+        json!({
+            "breakMinutes": 0,
+            "deductedBreakMinutes": 60,
+            "workedMinutes": 609,
+            "sessionCount": 3,
+            "workingDays": 2,
+            "autoAdjustedDays": 1,
+            "breakdown": {
+                "regularMinutes": 519,
+                "overtimeMinutes": 90,
+                "lateNightMinutes": 14,
+                "restDayMinutes": 0,
+            },
+        })
+    );
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_listing_in_workplace_timezone(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("update workplaces set timezone = 'America/Los_Angeles' where id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    // 2026-01-31T22:00 in Los Angeles, which is already February in UTC
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (3, 1, 1, 'clock-in', '2026-02-01T06:00:00Z', '2026-02-01T06:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let ids = response_json["attendanceRecords"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|record| record["id"].as_u64().unwrap())
+        .collect::<Vec<u64>>();
+    assert_eq!(ids, vec![1, 2, 3]);
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn attendance_record_listing_on_day_without_local_midnight(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    // Santiago skips from 2026-09-06T00:00-04:00 straight to 01:00-03:00
+    sqlx::query("update workplaces set timezone = 'America/Santiago' where id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (1, 1, 1, 'clock-in', '2026-09-06T03:30:00Z', '2026-09-06T03:30:00Z'), (2, 1, 1, 'clock-out', '2026-09-06T04:30:00Z', '2026-09-06T04:30:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?from=2026-09-06&to=2026-09-06")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let ids = response_json["attendanceRecords"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|record| record["id"].as_u64().unwrap())
+        .collect::<Vec<u64>>();
+    assert_eq!(ids, vec![2]);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_creation_with_backdated_conflict(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    // between the clock-in at 12:34:56 and the clock-out at 13:14:15
+    let datetime: DateTime<Local> = "2026-01-26T13:00:00Z".parse().unwrap();
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/attendance_records")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(CreationParams {
+            event: attendance_record::Event::ClockIn,
+            datetime,
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "error": "conflict",
+        "reason": "already-clocked-in",
+    });
+    assert_eq!(response_json, expected_json);
+
+    let attendance_records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = $1")
+        .bind(1)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(attendance_records.len(), 2);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_creation_breaking_following_record(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    for (event, datetime, reason) in [
+        // between the clock-in at 12:34:56 and the clock-out at 13:14:15
+        (
+            attendance_record::Event::ClockOut,
+            "2026-01-26T13:00:00Z",
+            "already-clocked-out",
+        ),
+        // before the clock-in at 12:34:56
+        (
+            attendance_record::Event::ClockIn,
+            "2026-01-26T09:00:00Z",
+            "already-clocked-in",
+        ),
+    ] {
+        let request = test::TestRequest::post()
+            .uri("/workplaces/1/attendance_records")
+            .insert_header(("Cookie", cookie_value.clone()))
+            .set_form(CreationParams {
+                event,
+                datetime: datetime.parse().unwrap(),
+            })
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT, "{datetime}");
+
+        let response_json: Value = test::read_body_json(response).await;
+        let expected_json = json!({
+            "error": "conflict",
+            "reason": reason,
+        });
+        assert_eq!(response_json, expected_json);
+    }
+
+    let attendance_records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = $1")
+        .bind(1)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(attendance_records.len(), 2);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_creation_with_backdated_sequence(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        event: attendance_record::Event,
+        datetime: DateTime<Local>,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    for (event, datetime) in [
+        (attendance_record::Event::ClockIn, "2026-01-26T14:00:00Z"),
+        (attendance_record::Event::ClockOut, "2026-01-26T18:00:00Z"),
+    ] {
+        let request = test::TestRequest::post()
+            .uri("/workplaces/1/attendance_records")
+            .insert_header(("Cookie", cookie_value.clone()))
+            .set_form(Params {
+                event,
+                datetime: datetime.parse().unwrap(),
+            })
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let attendance_records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = $1")
+        .bind(1)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(attendance_records.len(), 4);
+}
+
+#[derive(Serialize)]
+struct UpdateParams {
+    event: Option<attendance_record::Event>,
+    datetime: Option<DateTime<Local>>,
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_update(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let datetime: DateTime<Local> = "2026-01-26T12:00:00Z".parse().unwrap();
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1/attendance_records/1")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(UpdateParams {
+            event: None,
+            datetime: Some(datetime),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "attendanceRecord": {
+            "id": 1,
+            "userId": 1,
+            "event": "clock-in",
+            "recordedAt": "2026-01-26T12:00:00Z",
+        },
+    });
+    assert_eq!(response_json, expected_json);
+
+    let attendance_record: AttendanceRecord = sqlx::query_as(
+        "select id, workplace_id, user_id, event, recorded_at from attendance_records where id = $1",
+    )
+    .bind(1)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(attendance_record.event, attendance_record::Event::ClockIn);
+    assert_eq!(attendance_record.recorded_at, datetime.to_utc().into());
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_update_breaking_sequence(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (3, 1, 1, 'clock-in', '2026-01-26T14:00:00Z', '2026-01-26T14:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    for (id, params, reason) in [
+        // the clock-out moved before the clock-in it ends
+        (
+            2,
+            UpdateParams {
+                event: None,
+                datetime: Some("2026-01-26T12:00:00Z".parse().unwrap()),
+            },
+            "already-clocked-out",
+        ),
+        // the clock-in turned into a clock-out without a clock-in before it
+        (
+            1,
+            UpdateParams {
+                event: Some(attendance_record::Event::ClockOut),
+                datetime: None,
+            },
+            "already-clocked-out",
+        ),
+        // the clock-out moved past the next clock-in leaves two clock-ins in a row
+        (
+            2,
+            UpdateParams {
+                event: None,
+                datetime: Some("2026-01-26T15:00:00Z".parse().unwrap()),
+            },
+            "already-clocked-in",
+        ),
+    ] {
+        let request = test::TestRequest::patch()
+            .uri(&format!("/workplaces/1/attendance_records/{id}"))
+            .insert_header(("Cookie", cookie_value.clone()))
+            .set_form(params)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT, "{reason}");
+
+        let response_json: Value = test::read_body_json(response).await;
+        let expected_json = json!({
+            "error": "conflict",
+            "reason": reason,
+        });
+        assert_eq!(response_json, expected_json);
+    }
+
+    let attendance_records: Vec<AttendanceRecord> = sqlx::query_as("select id, workplace_id, user_id, event, recorded_at from attendance_records where workplace_id = $1 order by id")
+        .bind(1)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    let events = attendance_records
+        .iter()
+        .map(|record| (record.event.clone(), record.recorded_at.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (
+                attendance_record::Event::ClockIn,
+                "2026-01-26 12:34:56 UTC".to_string()
+            ),
+            (
+                attendance_record::Event::ClockOut,
+                "2026-01-26 13:14:15 UTC".to_string()
+            ),
+            (
+                attendance_record::Event::ClockIn,
+                "2026-01-26 14:00:00 UTC".to_string()
+            ),
+        ]
+    );
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_update_with_other_user(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(2);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1/attendance_records/1")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(UpdateParams {
+            event: Some(attendance_record::Event::ClockOut),
+            datetime: None,
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let attendance_record: AttendanceRecord = sqlx::query_as(
+        "select id, workplace_id, user_id, event, recorded_at from attendance_records where id = $1",
+    )
+    .bind(1)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(attendance_record.event, attendance_record::Event::ClockIn);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_update_on_other_workplace(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    // record#1 belongs to workplace#1, not to workplace#2
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/2/attendance_records/1")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(UpdateParams {
+            event: Some(attendance_record::Event::ClockOut),
+            datetime: None,
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_history(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let datetime: DateTime<Local> = "2026-01-27T00:00:00Z".parse().unwrap();
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/attendance_records")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(CreationParams {
+            event: attendance_record::Event::ClockIn,
+            datetime,
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let updated_datetime: DateTime<Local> = "2026-01-27T00:30:00Z".parse().unwrap();
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1/attendance_records/3")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(UpdateParams {
+            event: None,
+            datetime: Some(updated_datetime),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::delete()
+        .uri("/workplaces/1/attendance_records/3")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/3/history")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let history = response_json["history"].as_array().unwrap();
+    assert_eq!(history.len(), 3);
+
+    let actions = history
+        .iter()
+        .map(|audit| {
+            json!([
+                audit["action"],
+                audit["userId"],
+                audit["channel"],
+                audit["previousRecordedAt"],
+                audit["recordedAt"],
+            ])
+        })
+        .collect::<Vec<Value>>();
+    assert_eq!(
+        actions,
+        vec![
+            json!(["create", 1, "session", null, "2026-01-27T00:00:00Z"]),
+            json!([
+                "update",
+                1,
+                "session",
+                "2026-01-27T00:00:00Z",
+                "2026-01-27T00:30:00Z"
+            ]),
+            json!(["destroy", 1, "session", "2026-01-27T00:30:00Z", null]),
+        ]
+    );
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_history_without_changes(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/1/history")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json, json!({ "history": [] }));
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/99/history")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_history_with_other_user(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(2);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/1/history")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_trash_and_restore(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::delete()
+        .uri("/workplaces/1/attendance_records/2")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(
+        response_json["attendanceRecords"].as_array().unwrap().len(),
+        1
+    );
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/trash")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["retentionDays"], 30);
+    let trashed = response_json["attendanceRecords"].as_array().unwrap();
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(trashed[0]["id"], 2);
+    assert!(trashed[0]["deletedAt"].is_string());
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/attendance_records/2/restore")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "attendanceRecord": {
+            "id": 2,
+            "userId": 1,
+            "event": "clock-out",
+            "recordedAt": "2026-01-26T13:14:15Z",
+        },
+    });
+    assert_eq!(response_json, expected_json);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/trash")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response_json: Value = test::read_body_json(response).await;
+    assert!(
+        response_json["attendanceRecords"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/2/history")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response_json: Value = test::read_body_json(response).await;
+    let actions = response_json["history"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|audit| audit["action"].as_str().unwrap().to_owned())
+        .collect::<Vec<String>>();
+    assert_eq!(actions, vec!["destroy", "restore"]);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_restore_with_other_user(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("update attendance_records set deleted_at = '2026-01-27T00:00:00Z' where id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(2);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/attendance_records/1/restore")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let attendance_record: AttendanceRecord = sqlx::query_as(
+        "select id, workplace_id, user_id, event, recorded_at, deleted_at from attendance_records where id = $1",
+    )
+    .bind(1)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(attendance_record.deleted_at.is_some());
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_restore_breaking_sequence(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    // the clock-in was deleted and recorded again at another time
+    sqlx::query("update attendance_records set deleted_at = '2026-01-27T00:00:00Z' where id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (3, 1, 1, 'clock-in', '2026-01-26T12:00:00Z', '2026-01-27T00:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/attendance_records/1/restore")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "error": "conflict",
+        "reason": "already-clocked-in",
+    });
+    assert_eq!(response_json, expected_json);
+
+    let attendance_record: AttendanceRecord = sqlx::query_as(
+        "select id, workplace_id, user_id, event, recorded_at, deleted_at from attendance_records where id = $1",
+    )
+    .bind(1)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(attendance_record.deleted_at.is_some());
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn purging_trash(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+
+    let recently_deleted_at = Utc::now() - TimeDelta::days(1);
+    sqlx::query("update attendance_records set deleted_at = $1 where id = 1")
+        .bind(recently_deleted_at)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let long_ago_deleted_at = Utc::now() - TimeDelta::days(31);
+    sqlx::query("update attendance_records set deleted_at = $1 where id = 2")
+        .bind(long_ago_deleted_at)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    azarole::tasks::purge_trash(&app_state).await;
+
+    let ids: Vec<(u32,)> = sqlx::query_as("select id from attendance_records order by id")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![(1,)]);
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn attendance_record_listing_with_breaks(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (1, 1, 1, 'clock-in', '2026-01-26T00:00:00Z', '2026-01-26T00:00:00Z'), (2, 1, 1, 'break-start', '2026-01-26T03:00:00Z', '2026-01-26T03:00:00Z'), (3, 1, 1, 'break-end', '2026-01-26T03:45:00Z', '2026-01-26T03:45:00Z'), (4, 1, 1, 'break-start', '2026-01-26T08:30:00Z', '2026-01-26T08:30:00Z'), (5, 1, 1, 'clock-out', '2026-01-26T09:00:00Z', '2026-01-26T09:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!([
+        {
+            "userId": 1,
+            "clockInId": 1,
+            "clockOutId": 5,
+            "startedAt": "2026-01-26T00:00:00Z",
+            "endedAt": "2026-01-26T09:00:00Z",
+            "durationSeconds": 32400,
+            "breakSeconds": 4500,
+            "workedSeconds": 27900,
+            "breaks": [
+                {
+                    "startedAt": "2026-01-26T03:00:00Z",
+                    "endedAt": "2026-01-26T03:45:00Z",
+                },
+                {
+                    "startedAt": "2026-01-26T08:30:00Z",
+                    "endedAt": "2026-01-26T09:00:00Z",
+                },
+            ],
+            "status": "complete",
+        },
+    ]);
+    assert_eq!(response_json["workSessions"], expected_json);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/summary?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["summary"]["days"][25]["breakMinutes"], 75);
+    assert_eq!(response_json["summary"]["days"][25]["workedMinutes"], 465);
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn attendance_summary_with_break_rules(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (1, 1, 1, 'clock-in', '2026-01-26T00:00:00Z', '2026-01-26T00:00:00Z'), (2, 1, 1, 'break-start', '2026-01-26T03:00:00Z', '2026-01-26T03:00:00Z'), (3, 1, 1, 'break-end', '2026-01-26T03:30:00Z', '2026-01-26T03:30:00Z'), (4, 1, 1, 'clock-out', '2026-01-26T07:00:00Z', '2026-01-26T07:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/summary?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let day = &response_json["summary"]["days"][25];
+    assert_eq!(day["breakMinutes"], 30);
+    assert_eq!(day["deductedBreakMinutes"], 15);
+    assert_eq!(day["workedMinutes"], 375);
+    assert_eq!(day["autoAdjusted"], true);
+
+    // The raw records are left as they were recorded.
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(
+        response_json["attendanceRecords"].as_array().unwrap().len(),
+        4
+    );
+    assert_eq!(response_json["workSessions"][0]["workedSeconds"], 23400);
+
+    sqlx::query("update workplaces set break_rules = '' where id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/summary?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let response_json: Value = test::read_body_json(response).await;
+    let day = &response_json["summary"]["days"][25];
+    assert_eq!(day["deductedBreakMinutes"], 0);
+    assert_eq!(day["workedMinutes"], 390);
+    assert_eq!(day["autoAdjusted"], false);
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn attendance_summary_with_overtime(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    // 09:00 to 18:00 with a break of an hour, from Monday in the week before the month
+    // through Friday, which fills up the 40 hours of the week.
+    for date in [
+        "2025-12-29",
+        "2025-12-30",
+        "2025-12-31",
+        "2026-01-01",
+        "2026-01-02",
+    ] {
+        sqlx::query("insert into attendance_records (workplace_id, user_id, event, recorded_at, created_at) values (1, 1, 'clock-in', $1, $1), (1, 1, 'break-start', $2, $2), (1, 1, 'break-end', $3, $3), (1, 1, 'clock-out', $4, $4)")
+            .bind(format!("{date}T00:00:00Z"))
+            .bind(format!("{date}T03:00:00Z"))
+            .bind(format!("{date}T04:00:00Z"))
+            .bind(format!("{date}T09:00:00Z"))
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    // Saturday 22:00 to Sunday 02:00, then Sunday 10:00 to 13:00.
+    sqlx::query("insert into attendance_records (workplace_id, user_id, event, recorded_at, created_at) values (1, 1, 'clock-in', '2026-01-03T13:00:00Z', '2026-01-03T13:00:00Z'), (1, 1, 'clock-out', '2026-01-03T17:00:00Z', '2026-01-03T17:00:00Z'), (1, 1, 'clock-in', '2026-01-04T01:00:00Z', '2026-01-04T01:00:00Z'), (1, 1, 'clock-out', '2026-01-04T04:00:00Z', '2026-01-04T04:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/summary?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let days = response_json["summary"]["days"].as_array().unwrap();
+    assert_eq!(
+        days[1]["breakdown"],
+        json!({
+            "regularMinutes": 480,
+            "overtimeMinutes": 0,
+            "lateNightMinutes": 0,
+            "restDayMinutes": 0,
+        })
+    );
+    assert_eq!(
+        days[2]["breakdown"],
+        json!({
+            "regularMinutes": 0,
+            "overtimeMinutes": 240,
+            "lateNightMinutes": 240,
+            "restDayMinutes": 0,
+        })
+    );
+    assert_eq!(
+        days[3]["breakdown"],
+        json!({
+            "regularMinutes": 0,
+            "overtimeMinutes": 0,
+            "lateNightMinutes": 0,
+            "restDayMinutes": 180,
+        })
+    );
+    assert_eq!(
+        response_json["summary"]["total"]["breakdown"],
+        json!({
+            "regularMinutes": 960,
+            "overtimeMinutes": 240,
+            "lateNightMinutes": 240,
+            "restDayMinutes": 180,
+        })
+    );
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_listing_as_csv(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1&format=csv")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+    assert_eq!(
+        response.headers().get("Content-Disposition").unwrap(),
+        "attachment; filename=\"attendances-2026-01.csv\""
+    );
+
+    let body = test::read_body(response).await;
+    let body = std::str::from_utf8(&body).unwrap();
+    let lines = body.lines().collect::<Vec<&str>>();
+    assert_eq!(lines.len(), 32);
+    assert_eq!(lines[0], "date,clock_in,clock_out,break_hours,worked_hours");
+    assert_eq!(lines[1], "2026-01-01,,,0.00,0.00");
+    assert_eq!(
+        lines[26],
+        "2026-01-26,2026-01-26 21:34,2026-01-26 22:14,0.00,0.65"
+    );
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_listing_with_csv_accept_header(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .insert_header(("Accept", "text/csv"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1&format=json")
+        .insert_header(("Cookie", cookie_value))
+        .insert_header(("Accept", "text/csv"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "application/json"
+    );
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_listing_with_date_range(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (3, 1, 1, 'clock-in', '2026-02-01T00:00:00Z', '2026-02-01T00:00:00Z'), (4, 1, 1, 'clock-out', '2026-02-01T08:00:00Z', '2026-02-01T08:00:00Z'), (5, 1, 1, 'clock-in', '2026-02-03T00:00:00Z', '2026-02-03T00:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?from=2026-01-26&to=2026-02-02")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["year"], Value::Null);
+    assert_eq!(response_json["month"], Value::Null);
+    assert_eq!(response_json["from"], "2026-01-26");
+    assert_eq!(response_json["to"], "2026-02-02");
+    let ids = response_json["attendanceRecords"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|record| record["id"].as_u64().unwrap())
+        .collect::<Vec<u64>>();
+    assert_eq!(ids, vec![1, 2, 3, 4]);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/summary?from=2026-01-26&to=2026-02-02")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let days = response_json["summary"]["days"].as_array().unwrap();
+    assert_eq!(days.len(), 8);
+    assert_eq!(days[6]["date"], "2026-02-01");
+    assert_eq!(days[6]["workedMinutes"], 435);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_listing_with_iso_week(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?week=2026-W05")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["from"], "2026-01-26");
+    assert_eq!(response_json["to"], "2026-02-01");
+    assert_eq!(
+        response_json["attendanceRecords"].as_array().unwrap().len(),
+        2
+    );
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?week=2026-W05&format=csv")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Disposition").unwrap(),
+        "attachment; filename=\"attendances-2026-01-26-2026-02-01.csv\""
+    );
+
+    let body = test::read_body(response).await;
+    assert_eq!(std::str::from_utf8(&body).unwrap().lines().count(), 8);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_listing_with_invalid_period(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    for query in [
+        "from=2026-02-01&to=2026-01-01",
+        "from=2026-01-01",
+        "from=2025-01-01&to=2026-12-31",
+        "week=2026-W54",
+        "year=2026&week=2026-W05",
+        "year=2026&month=13",
+        "year=-1&month=1",
+        "year=300000&month=1",
+    ] {
+        let request = test::TestRequest::get()
+            .uri(&format!("/workplaces/1/attendance_records?{query}"))
+            .insert_header(("Cookie", cookie_value.clone()))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{query}");
+    }
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn attendance_record_listing_with_closing_day(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("update workplaces set closing_day = 20 where id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (3, 1, 1, 'clock-in', '2025-12-21T00:00:00Z', '2025-12-21T00:00:00Z'), (4, 1, 1, 'clock-out', '2025-12-21T01:00:00Z', '2025-12-21T01:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["from"], "2025-12-21");
+    assert_eq!(response_json["to"], "2026-01-20");
+    let ids = response_json["attendanceRecords"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|record| record["id"].as_u64().unwrap())
+        .collect::<Vec<u64>>();
+    assert_eq!(ids, vec![3, 4]);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/summary?year=2026&month=2")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["from"], "2026-01-21");
+    assert_eq!(response_json["to"], "2026-02-20");
+    let days = response_json["summary"]["days"].as_array().unwrap();
+    assert_eq!(days.len(), 31);
+    assert_eq!(days[5]["date"], "2026-01-26");
+    assert_eq!(days[5]["workedMinutes"], 39);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=2&format=csv")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let body = std::str::from_utf8(&body).unwrap();
+    let lines = body.lines().collect::<Vec<&str>>();
+    assert_eq!(lines.len(), 32);
+    assert!(lines[1].starts_with("2026-01-21,"));
+    assert!(lines[31].starts_with("2026-02-20,"));
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn attendance_summary_with_closing_day_past_month_end(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("update workplaces set closing_day = 30 where id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    for (month, from, to) in [
+        (2, "2026-01-31", "2026-02-28"),
+        (3, "2026-03-01", "2026-03-30"),
+    ] {
+        let request = test::TestRequest::get()
+            .uri(&format!(
+                "/workplaces/1/attendance_records/summary?year=2026&month={month}"
+            ))
+            .insert_header(("Cookie", cookie_value.clone()))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response_json: Value = test::read_body_json(response).await;
+        assert_eq!(response_json["from"], from);
+        assert_eq!(response_json["to"], to);
+    }
+}
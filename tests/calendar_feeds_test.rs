@@ -0,0 +1,146 @@
+use actix_web::{App, http::StatusCode, test, web::Data};
+use chrono::{TimeDelta, Utc};
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+mod common;
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn calendar_feed(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let clock_in = Utc::now() - TimeDelta::hours(3);
+    let clock_out = clock_in + TimeDelta::minutes(150);
+    sqlx::query("insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at) values (1, 1, 1, 'clock-in', $1, $1), (2, 1, 1, 'clock-out', $2, $2), (3, 1, 1, 'clock-in', $2, $2)")
+        .bind(clock_in)
+        .bind(clock_out)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/calendar_feed")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["calendarFeed"]["workplaceId"], 1);
+    let feed_path = response_json["path"].as_str().unwrap().to_owned();
+
+    let request = test::TestRequest::get().uri(&feed_path).to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/calendar; charset=utf-8"
+    );
+
+    let body = test::read_body(response).await;
+    let body = std::str::from_utf8(&body).unwrap();
+    assert!(body.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(body.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(body.matches("BEGIN:VEVENT").count(), 1);
+    assert!(body.contains("UID:attendance-record-1@azarole\r\n"));
+    assert!(body.contains(&format!(
+        "DTSTART:{}\r\n",
+        clock_in.format("%Y%m%dT%H%M%SZ")
+    )));
+    assert!(body.contains(&format!("DTEND:{}\r\n", clock_out.format("%Y%m%dT%H%M%SZ"))));
+    assert!(body.contains("DESCRIPTION:Worked 2:30\\, break 0:00\r\n"));
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/calendar_feed")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn calendar_feed_revocation(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let mut feed_paths = Vec::new();
+    for _ in 0..2 {
+        let request = test::TestRequest::post()
+            .uri("/workplaces/1/calendar_feed")
+            .insert_header(("Cookie", cookie_value.clone()))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response_json: Value = test::read_body_json(response).await;
+        feed_paths.push(response_json["path"].as_str().unwrap().to_owned());
+    }
+
+    // Issuing a new token revokes the previous one.
+    let request = test::TestRequest::get().uri(&feed_paths[0]).to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = test::TestRequest::get().uri(&feed_paths[1]).to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::delete()
+        .uri("/workplaces/1/calendar_feed")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get().uri(&feed_paths[1]).to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/calendar_feed")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn calendar_feed_creation_with_other_user(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/3/calendar_feed")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = test::TestRequest::get()
+        .uri("/calendar_feeds/invalid-token.ics")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
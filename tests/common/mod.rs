@@ -4,10 +4,16 @@ use actix_session::{SessionMiddleware, storage::CookieSessionStore};
 use actix_web::cookie::{Cookie, CookieJar, Key};
 use azarole::{
     AppState,
-    config::{AppConfig, ApplicationConfig, DatabaseConfig, FrontendConfig, ServerConfig},
+    config::{
+        ApiKeysConfig, AppConfig, ApplicationConfig, DatabaseConfig, FrontendConfig,
+        IdempotencyConfig, RateLimitConfig, ServerConfig, TrashConfig,
+    },
     context::DatabaseContext,
+    rate_limiter::RateLimiter,
     repositories::RdbRepositories,
-    secrets::{ApikeyConfig, Base64Encoded, GoogleAuthConfig, Secrets, SessionConfig},
+    secrets::{
+        ApikeyConfig, Base64Encoded, GoogleAuthConfig, PreviousSecretKeys, Secrets, SessionConfig,
+    },
 };
 use base64::{Engine as _, engine::general_purpose::URL_SAFE};
 use sqlx::SqlitePool;
@@ -26,11 +32,21 @@ fn create_config() -> ApplicationConfig {
         bind: "127.0.0.1".to_string(),
         port: 3000,
     };
+    let trash = TrashConfig { retention_days: 30 };
+    let api_keys = ApiKeysConfig {
+        rotation_grace_period_minutes: 60,
+    };
+    let rate_limit = RateLimitConfig::default();
+    let idempotency = IdempotencyConfig::default();
     ApplicationConfig {
         app,
         database,
         frontend,
         server,
+        trash,
+        api_keys,
+        rate_limit,
+        idempotency,
     }
 }
 
@@ -42,6 +58,8 @@ fn create_secrets() -> Secrets {
                 .decode("AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=")
                 .unwrap(),
         ),
+        digesting_secret_key_id: "primary".to_string(),
+        previous_digesting_secret_keys: PreviousSecretKeys::default(),
     };
     let google_auth = GoogleAuthConfig {
         client_id: "dummy-google-client-id".to_string(),
@@ -69,6 +87,7 @@ pub fn create_app_state(pool: SqlitePool) -> AppState {
         database,
         repositories,
         secrets,
+        rate_limiter: RateLimiter::default(),
     }
 }
 
@@ -1,5 +1,5 @@
-insert into attendance_records (id, workplace_id, event, recorded_at, created_at)
+insert into attendance_records (id, workplace_id, user_id, event, recorded_at, created_at)
 values
-  (1, 1, 'clock-in', '2026-01-26T12:34:56Z', '2026-01-26T12:34:56Z'),
-  (2, 1, 'clock-out', '2026-01-26T13:14:15Z', '2026-01-26T13:14:15Z')
+  (1, 1, 1, 'clock-in', '2026-01-26T12:34:56Z', '2026-01-26T12:34:56Z'),
+  (2, 1, 1, 'clock-out', '2026-01-26T13:14:15Z', '2026-01-26T13:14:15Z')
 ;
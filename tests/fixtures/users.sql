@@ -1,5 +1,7 @@
 insert into users (id, created_at)
 values
   (1, '2026-01-09T12:34:56Z'),
-  (2, '2026-01-11T10:20:30Z')
+  (2, '2026-01-11T10:20:30Z'),
+  (3, '2026-01-12T09:00:00Z'),
+  (4, '2026-01-13T09:00:00Z')
 ;
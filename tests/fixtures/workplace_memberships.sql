@@ -0,0 +1,5 @@
+insert into workplace_memberships (id, workplace_id, user_id, role, created_at, updated_at)
+values
+  (1, 1, 3, 'manager', '2026-01-12T09:00:00Z', '2026-01-12T09:00:00Z'),
+  (2, 1, 4, 'member', '2026-01-13T09:00:00Z', '2026-01-13T09:00:00Z')
+;
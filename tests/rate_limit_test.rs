@@ -0,0 +1,170 @@
+use actix_web::{App, http::StatusCode, test, web::Data};
+use azarole::config::RateLimitBucketConfig;
+use sqlx::SqlitePool;
+
+mod common;
+
+const VALID_API_KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn limiting_requests_per_api_key(pool: SqlitePool) {
+    let mut app_state = common::create_app_state(pool);
+    app_state.config.rate_limit.per_key = RateLimitBucketConfig {
+        requests: 2,
+        window_seconds: 60,
+    };
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    for remaining in ["1", "0"] {
+        let request = test::TestRequest::get()
+            .uri("/api/workplaces/1/attendance_records")
+            .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get("x-ratelimit-limit").unwrap(), "2");
+        assert_eq!(
+            response.headers().get("x-ratelimit-remaining").unwrap(),
+            remaining
+        );
+    }
+
+    let request = test::TestRequest::get()
+        .uri("/api/workplaces/1/attendance_records")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .to_request();
+    let result = test::try_call_service(&app, request).await;
+    let response = result.unwrap_err().error_response();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        response.headers().get("x-ratelimit-remaining").unwrap(),
+        "0"
+    );
+    let retry_after: u64 = response
+        .headers()
+        .get("retry-after")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn limiting_requests_per_ip(pool: SqlitePool) {
+    let mut app_state = common::create_app_state(pool);
+    app_state.config.rate_limit.per_ip = RateLimitBucketConfig {
+        requests: 1,
+        window_seconds: 60,
+    };
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let request = test::TestRequest::get()
+        .uri("/api/workplaces/1/attendance_records")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .peer_addr("192.0.2.10:50000".parse().unwrap())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri("/api/workplaces/1/attendance_records")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .peer_addr("192.0.2.10:50001".parse().unwrap())
+        .to_request();
+    let result = test::try_call_service(&app, request).await;
+    let response = result.unwrap_err().error_response();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers().get("x-ratelimit-limit").unwrap(), "1");
+
+    let request = test::TestRequest::get()
+        .uri("/api/workplaces/1/attendance_records")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .peer_addr("192.0.2.20:50000".parse().unwrap())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn limited_requests_leave_usage_unrecorded(pool: SqlitePool) {
+    let mut app_state = common::create_app_state(pool.clone());
+    app_state.config.rate_limit.per_key = RateLimitBucketConfig {
+        requests: 1,
+        window_seconds: 60,
+    };
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let request = test::TestRequest::get()
+        .uri("/api/workplaces/1/attendance_records")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    sqlx::query("update api_keys set last_used_at = null where id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let request = test::TestRequest::get()
+        .uri("/api/workplaces/1/attendance_records")
+        .insert_header(("Authorization", format!("Bearer {VALID_API_KEY}")))
+        .to_request();
+    let result = test::try_call_service(&app, request).await;
+    let response = result.unwrap_err().error_response();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let last_used_at: Option<String> =
+        sqlx::query_scalar("select last_used_at from api_keys where id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(last_used_at, None);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn limiting_requests_with_invalid_api_key_per_ip(pool: SqlitePool) {
+    let mut app_state = common::create_app_state(pool);
+    app_state.config.rate_limit.per_ip = RateLimitBucketConfig {
+        requests: 1,
+        window_seconds: 60,
+    };
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    for expected_status in [StatusCode::UNAUTHORIZED, StatusCode::TOO_MANY_REQUESTS] {
+        let request = test::TestRequest::get()
+            .uri("/api/workplaces/1/attendance_records")
+            .insert_header(("Authorization", "Bearer invalid-api-key"))
+            .peer_addr("192.0.2.10:50000".parse().unwrap())
+            .to_request();
+        let result = test::try_call_service(&app, request).await;
+        let response = result.unwrap_err().error_response();
+        assert_eq!(response.status(), expected_status);
+    }
+}
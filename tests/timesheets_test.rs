@@ -0,0 +1,286 @@
+use actix_web::{App, http::StatusCode, test, web::Data};
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
+
+mod common;
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn timesheet_before_submission(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/timesheets/2026/1")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "userId": 1,
+        "year": 2026,
+        "month": 1,
+        "state": "open",
+        "comment": null,
+        "startsAt": "2025-12-31T15:00:00Z",
+        "endsAt": "2026-01-31T15:00:00Z",
+        "submittedAt": null,
+        "reviewedBy": null,
+        "reviewedAt": null,
+    });
+    assert_eq!(response_json["timesheet"], expected_json);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "workplace_memberships", "attendance_records"))]
+async fn timesheet_approval_locks_period(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let owner_cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/timesheets/2026/1/submit")
+        .insert_header(("Cookie", owner_cookie_value.clone()))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response_json["timesheet"]["state"], "submitted");
+
+    let manager_cookie_value = common::generate_cookie_value_with_signin_user(3);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/timesheets/2026/1/approve")
+        .insert_header(("Cookie", manager_cookie_value.clone()))
+        .set_form([("user_id", "1")])
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response_json["timesheet"]["state"], "approved");
+    assert_eq!(response_json["timesheet"]["reviewedBy"], 3);
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/attendance_records")
+        .insert_header(("Cookie", owner_cookie_value.clone()))
+        .set_form([("event", "clock-in"), ("datetime", "2026-01-27T00:00:00Z")])
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["reason"], "period-locked");
+
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1/attendance_records/1")
+        .insert_header(("Cookie", manager_cookie_value.clone()))
+        .set_form([("datetime", "2026-01-26T12:00:00Z")])
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let request = test::TestRequest::delete()
+        .uri("/workplaces/1/attendance_records/2")
+        .insert_header(("Cookie", owner_cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/attendance_records")
+        .insert_header(("Cookie", owner_cookie_value.clone()))
+        .set_form([("event", "clock-in"), ("datetime", "2026-02-02T00:00:00Z")])
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/timesheets/2026/1/reopen")
+        .insert_header(("Cookie", manager_cookie_value))
+        .set_form([("user_id", "1"), ("comment", "fixing a typo")])
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response_json["timesheet"]["state"], "open");
+    assert_eq!(response_json["timesheet"]["comment"], "fixing a typo");
+
+    let request = test::TestRequest::delete()
+        .uri("/workplaces/1/attendance_records/2")
+        .insert_header(("Cookie", owner_cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "workplace_memberships"))]
+async fn timesheet_self_approval(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let owner_cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/timesheets/2026/1/submit")
+        .insert_header(("Cookie", owner_cookie_value.clone()))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response_json["timesheet"]["state"], "submitted");
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/timesheets/2026/1/approve")
+        .insert_header(("Cookie", owner_cookie_value.clone()))
+        .set_form([("user_id", "1")])
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/timesheets/2026/1")
+        .insert_header(("Cookie", owner_cookie_value))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response_json["timesheet"]["state"], "submitted");
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "workplace_memberships"))]
+async fn timesheet_return_and_resubmission(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let member_cookie_value = common::generate_cookie_value_with_signin_user(4);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/timesheets/2026/1/submit")
+        .insert_header(("Cookie", member_cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/timesheets/2026/1/approve")
+        .insert_header(("Cookie", member_cookie_value.clone()))
+        .set_form([("user_id", "4")])
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let owner_cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/timesheets/2026/1/return")
+        .insert_header(("Cookie", owner_cookie_value.clone()))
+        .set_form([("user_id", "4")])
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/timesheets/2026/1/return")
+        .insert_header(("Cookie", owner_cookie_value.clone()))
+        .set_form([
+            ("user_id", "4"),
+            ("comment", "missing clock-out on the 5th"),
+        ])
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/timesheets/2026/1")
+        .insert_header(("Cookie", member_cookie_value.clone()))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response_json["timesheet"]["state"], "returned");
+    assert_eq!(
+        response_json["timesheet"]["comment"],
+        "missing clock-out on the 5th"
+    );
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/timesheets/2026/1/approve")
+        .insert_header(("Cookie", owner_cookie_value.clone()))
+        .set_form([("user_id", "4")])
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["reason"], "not-submitted");
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/timesheets/2026/1/submit")
+        .insert_header(("Cookie", member_cookie_value.clone()))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response_json["timesheet"]["state"], "submitted");
+    assert_eq!(response_json["timesheet"]["comment"], Value::Null);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/timesheets/2026/1?user_id=1")
+        .insert_header(("Cookie", member_cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/timesheets?year=2026")
+        .insert_header(("Cookie", owner_cookie_value))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    let timesheets = response_json["timesheets"].as_array().unwrap();
+    assert_eq!(timesheets.len(), 1);
+    assert_eq!(timesheets[0]["userId"], 4);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "api_keys"))]
+async fn timesheet_state_with_api_key(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let valid_api_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0-P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f";
+    let request = test::TestRequest::get()
+        .uri("/api/workplaces/1/timesheets/2026/1")
+        .insert_header(("Authorization", format!("Bearer {valid_api_key}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["timesheet"]["state"], "open");
+}
@@ -0,0 +1,308 @@
+use actix_web::{App, http::StatusCode, test, web::Data};
+use serde::Serialize;
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
+
+mod common;
+
+#[sqlx::test(fixtures("users", "workplaces", "workplace_memberships"))]
+async fn membership_listing(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(3);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/memberships")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "memberships": [
+            { "userId": 1, "role": "owner" },
+            { "userId": 3, "role": "manager" },
+            { "userId": 4, "role": "member" },
+        ],
+    });
+    assert_eq!(response_json, expected_json);
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(4);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/memberships")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    let expected_json = json!({
+        "memberships": [
+            { "userId": 4, "role": "member" },
+        ],
+    });
+    assert_eq!(response_json, expected_json);
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn membership_creation(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        user_id: u32,
+        role: String,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/memberships")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(Params {
+            user_id: 2,
+            role: "member".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "membership": { "userId": 2, "role": "member" },
+    });
+    assert_eq!(response_json, expected_json);
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/memberships")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(Params {
+            user_id: 2,
+            role: "manager".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/memberships")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(Params {
+            user_id: 3,
+            role: "owner".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(2);
+    let request = test::TestRequest::get()
+        .uri("/workplaces")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    let workplaces: Vec<_> = response_json["workplaces"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|workplace| (workplace["id"].clone(), workplace["role"].clone()))
+        .collect();
+    assert_eq!(
+        workplaces,
+        vec![(json!(1), json!("member")), (json!(3), json!("owner"))]
+    );
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "workplace_memberships"))]
+async fn membership_changes_by_manager(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        role: String,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(3);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1/memberships/4")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(Params {
+            role: "manager".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1")
+        .insert_header(("Cookie", cookie_value))
+        .set_form([("name", "renamed-by-manager")])
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "workplace_memberships"))]
+async fn membership_update_and_deletion(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        role: String,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1/memberships/4")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(Params {
+            role: "manager".to_owned(),
+        })
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    let expected_json = json!({
+        "membership": { "userId": 4, "role": "manager" },
+    });
+    assert_eq!(response_json, expected_json);
+
+    let request = test::TestRequest::delete()
+        .uri("/workplaces/1/memberships/4")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(4);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "workplace_memberships", "attendance_records"))]
+async fn attendance_visibility_by_role(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct CreationParams {
+        event: String,
+        datetime: String,
+    }
+
+    let member_cookie_value = common::generate_cookie_value_with_signin_user(4);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/attendance_records")
+        .insert_header(("Cookie", member_cookie_value.clone()))
+        .set_form(CreationParams {
+            event: "clock-in".to_owned(),
+            datetime: "2026-01-26T23:00:00Z".to_owned(),
+        })
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response_json["attendanceRecord"]["userId"], 4);
+    let member_record_id = response_json["attendanceRecord"]["id"].as_i64().unwrap();
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1")
+        .insert_header(("Cookie", member_cookie_value.clone()))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    let ids: Vec<_> = response_json["attendanceRecords"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|record| record["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![member_record_id]);
+    assert_eq!(response_json["workplace"]["role"], "member");
+
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1/attendance_records/1")
+        .insert_header(("Cookie", member_cookie_value))
+        .set_form([("event", "clock-out")])
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let manager_cookie_value = common::generate_cookie_value_with_signin_user(3);
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records?year=2026&month=1")
+        .insert_header(("Cookie", manager_cookie_value.clone()))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(
+        response_json["attendanceRecords"].as_array().unwrap().len(),
+        3
+    );
+    let session_users: Vec<_> = response_json["workSessions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|session| session["userId"].as_i64().unwrap())
+        .collect();
+    assert_eq!(session_users, vec![1, 4]);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/summary?year=2026&month=1&user_id=4")
+        .insert_header(("Cookie", manager_cookie_value.clone()))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response_json["userId"], 4);
+    assert_eq!(response_json["summary"]["total"]["sessionCount"], 1);
+
+    let request = test::TestRequest::patch()
+        .uri(&format!(
+            "/workplaces/1/attendance_records/{member_record_id}"
+        ))
+        .insert_header(("Cookie", manager_cookie_value))
+        .set_form([("datetime", "2026-01-26T22:30:00Z")])
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response_json["attendanceRecord"]["userId"], 4);
+    assert_eq!(
+        response_json["attendanceRecord"]["recordedAt"],
+        "2026-01-26T22:30:00Z"
+    );
+}
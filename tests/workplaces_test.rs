@@ -1,3 +1,5 @@
+#![allow(clippy::iter_count)]
+
 use actix_web::{App, http::StatusCode, test, web::Data};
 use azarole::{models::User, repositories::RepositoryFactory};
 use serde::Serialize;
@@ -53,10 +55,28 @@ async fn workplace_listing(pool: SqlitePool) {
             {
                 "id": 1,
                 "name": "workplace-01-for-user-01",
+                "timezone": "Asia/Tokyo",
+                "breakRules": "360:45,480:60",
+                "overtimeRules": {
+                    "dailyThresholdMinutes": 480,
+                    "weeklyThresholdMinutes": 2400,
+                    "restDays": "sun",
+                },
+                "closingDay": 31,
+                "role": "owner",
             },
             {
                 "id": 2,
                 "name": "workplace-02-for-user-01",
+                "timezone": "Asia/Tokyo",
+                "breakRules": "360:45,480:60",
+                "overtimeRules": {
+                    "dailyThresholdMinutes": 480,
+                    "weeklyThresholdMinutes": 2400,
+                    "restDays": "sun",
+                },
+                "closingDay": 31,
+                "role": "owner",
             },
         ],
     });
@@ -127,15 +147,595 @@ async fn workplace_creation(pool: SqlitePool) {
         "workplace": {
             "id": 1,
             "name": "test-workplace",
+            "timezone": "Asia/Tokyo",
+            "breakRules": "360:45,480:60",
+            "overtimeRules": {
+                "dailyThresholdMinutes": 480,
+                "weeklyThresholdMinutes": 2400,
+                "restDays": "sun",
+            },
+            "closingDay": 31,
+            "role": "owner",
         },
     });
     assert_eq!(response_json, expected_json);
 
     let user = User { id: 1.into() };
     let repository = app_state.repositories.workplace();
-    let workplaces = repository.list(&user).await.unwrap();
+    let workplaces = repository.list(&user, false).await.unwrap();
     assert_eq!(workplaces.iter().count(), 1);
 
     let workplace = workplaces.first().unwrap();
     assert_eq!(workplace.name, "test-workplace");
 }
+
+#[sqlx::test(fixtures("users"))]
+async fn workplace_creation_with_timezone(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        name: String,
+        timezone: String,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/workplaces")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(Params {
+            name: "test-workplace".to_owned(),
+            timezone: "Europe/Berlin".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "workplace": {
+            "id": 1,
+            "name": "test-workplace",
+            "timezone": "Europe/Berlin",
+            "breakRules": "360:45,480:60",
+            "overtimeRules": {
+                "dailyThresholdMinutes": 480,
+                "weeklyThresholdMinutes": 2400,
+                "restDays": "sun",
+            },
+            "closingDay": 31,
+            "role": "owner",
+        },
+    });
+    assert_eq!(response_json, expected_json);
+}
+
+#[sqlx::test(fixtures("users"))]
+async fn workplace_creation_with_invalid_timezone(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        name: String,
+        timezone: String,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/workplaces")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(Params {
+            name: "test-workplace".to_owned(),
+            timezone: "Mars/Olympus_Mons".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let user = User { id: 1.into() };
+    let repository = app_state.repositories.workplace();
+    let workplaces = repository.list(&user, false).await.unwrap();
+    assert!(workplaces.is_empty());
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn workplace_update(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        timezone: String,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(Params {
+            timezone: "America/New_York".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    let expected_json = json!({
+        "workplace": {
+            "id": 1,
+            "name": "workplace-01-for-user-01",
+            "timezone": "America/New_York",
+            "breakRules": "360:45,480:60",
+            "overtimeRules": {
+                "dailyThresholdMinutes": 480,
+                "weeklyThresholdMinutes": 2400,
+                "restDays": "sun",
+            },
+            "closingDay": 31,
+            "role": "owner",
+        },
+    });
+    assert_eq!(response_json, expected_json);
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn workplace_update_with_other_user(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        timezone: String,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(2);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(Params {
+            timezone: "America/New_York".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let user = User { id: 1.into() };
+    let repository = app_state.repositories.workplace();
+    let workplace = repository.find(&user, 1.into()).await.unwrap();
+    assert_eq!(workplace.timezone.name(), "Asia/Tokyo");
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn workplace_update_with_break_rules(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        break_rules: String,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(Params {
+            break_rules: "480:60, 300:30".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["workplace"]["breakRules"], "300:30,480:60");
+
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(Params {
+            break_rules: "".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["workplace"]["breakRules"], "");
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn workplace_update_with_invalid_break_rules(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        break_rules: String,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(Params {
+            break_rules: "six hours".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let user = User { id: 1.into() };
+    let repository = app_state.repositories.workplace();
+    let workplace = repository.find(&user, 1.into()).await.unwrap();
+    assert_eq!(workplace.break_rules.to_string(), "360:45,480:60");
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn workplace_update_with_overtime_rules(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        daily_overtime_threshold_minutes: u32,
+        weekly_overtime_threshold_minutes: u32,
+        rest_days: String,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(Params {
+            daily_overtime_threshold_minutes: 420,
+            weekly_overtime_threshold_minutes: 2100,
+            rest_days: "sun,Saturday".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(
+        response_json["workplace"]["overtimeRules"],
+        json!({
+            "dailyThresholdMinutes": 420,
+            "weeklyThresholdMinutes": 2100,
+            "restDays": "sat,sun",
+        })
+    );
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn workplace_update_with_closing_day(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        closing_day: u32,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .set_form(Params { closing_day: 20 })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["workplace"]["closingDay"], 20);
+
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(Params { closing_day: 0 })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn workplace_rename(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    #[derive(Serialize)]
+    struct Params {
+        name: String,
+    }
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::patch()
+        .uri("/workplaces/1")
+        .insert_header(("Cookie", cookie_value))
+        .set_form(Params {
+            name: "renamed-workplace".to_owned(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["workplace"]["name"], "renamed-workplace");
+    assert_eq!(response_json["workplace"]["timezone"], "Asia/Tokyo");
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn workplace_archive_and_unarchive(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/archive")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert!(response_json["workplace"]["archivedAt"].is_string());
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    let ids: Vec<_> = response_json["workplaces"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|workplace| workplace["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![2]);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces?include_archived=true")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response_json: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response_json["workplaces"].as_array().unwrap().len(), 2);
+
+    let request = test::TestRequest::get()
+        .uri("/workplaces/1/attendance_records/summary?year=2026&month=1")
+        .insert_header(("Cookie", cookie_value.clone()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri("/workplaces/1/unarchive")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert!(response_json["workplace"].get("archivedAt").is_none());
+
+    let user = User { id: 1.into() };
+    let repository = app_state.repositories.workplace();
+    let workplaces = repository.list(&user, false).await.unwrap();
+    assert_eq!(workplaces.len(), 2);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn workplace_deletion_with_attendance_records(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::delete()
+        .uri("/workplaces/1")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response_json: Value = test::read_body_json(response).await;
+    assert_eq!(response_json["reason"], "has-attendance-records");
+
+    let user = User { id: 1.into() };
+    let repository = app_state.repositories.workplace();
+    assert!(repository.find(&user, 1.into()).await.is_ok());
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "attendance_records"))]
+async fn workplace_deletion_with_cascade(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    sqlx::query("insert into timesheets (workplace_id, user_id, year, month, state, starts_at, ends_at, submitted_at, reviewed_by, reviewed_at, created_at, updated_at) values (1, 1, 2026, 1, 'approved', '2025-12-31T15:00:00Z', '2026-01-31T15:00:00Z', '2026-02-01T00:00:00Z', 3, '2026-02-02T00:00:00Z', '2026-02-01T00:00:00Z', '2026-02-02T00:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::delete()
+        .uri("/workplaces/1?attendance_records=cascade")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let user = User { id: 1.into() };
+    let repository = app_state.repositories.workplace();
+    assert!(repository.find(&user, 1.into()).await.is_err());
+
+    let count: i64 =
+        sqlx::query_scalar("select count(*) from attendance_records where workplace_id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(count, 0);
+
+    let count: i64 = sqlx::query_scalar("select count(*) from timesheets where workplace_id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn workplace_deletion_without_attendance_records(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(1);
+    let request = test::TestRequest::delete()
+        .uri("/workplaces/2")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let user = User { id: 1.into() };
+    let repository = app_state.repositories.workplace();
+    let workplaces = repository.list(&user, true).await.unwrap();
+    assert_eq!(workplaces.len(), 1);
+}
+
+#[sqlx::test(fixtures("users", "workplaces"))]
+async fn workplace_deletion_with_other_user(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool);
+    let app = test::init_service(
+        App::new()
+            .wrap(common::create_session_middleware())
+            .app_data(Data::new(app_state.clone()))
+            .configure(azarole::handlers::routes),
+    )
+    .await;
+
+    let cookie_value = common::generate_cookie_value_with_signin_user(2);
+    let request = test::TestRequest::delete()
+        .uri("/workplaces/2")
+        .insert_header(("Cookie", cookie_value))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(fixtures("users", "workplaces", "workplace_memberships", "attendance_records"))]
+async fn workplace_cascading_deletion_by_manager(pool: SqlitePool) {
+    let app_state = common::create_app_state(pool.clone());
+
+    let manager = User { id: 3.into() };
+    let repository = app_state.repositories.workplace();
+    let workplace = repository.find(&manager, 1.into()).await.unwrap();
+    let result = repository.delete_cascading(&workplace, true).await;
+    assert!(result.is_err());
+
+    let memberships: i64 =
+        sqlx::query_scalar("select count(*) from workplace_memberships where workplace_id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(memberships, 2);
+
+    let attendance_records: i64 =
+        sqlx::query_scalar("select count(*) from attendance_records where workplace_id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(attendance_records, 2);
+}